@@ -0,0 +1,418 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector, TableColumn},
+    poly::Rotation,
+};
+
+/// A bitwise operation served by the table. The discriminant is the tag of its rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitwiseOp {
+    And = 1,
+    Or = 2,
+    Xor = 3,
+    /// Unary; its rows have `rhs = 0`.
+    Not = 4,
+}
+
+impl BitwiseOp {
+    pub const ALL: [BitwiseOp; 4] =
+        [BitwiseOp::And, BitwiseOp::Or, BitwiseOp::Xor, BitwiseOp::Not];
+
+    /// Returns the tag identifying this operation's rows in the table.
+    pub fn tag(self) -> u64 {
+        self as u64
+    }
+
+    /// Computes the operation natively on `bits`-bit operands.
+    pub fn apply(self, lhs: u64, rhs: u64, bits: usize) -> u64 {
+        let mask = (1 << bits) - 1;
+        match self {
+            BitwiseOp::And => lhs & rhs,
+            BitwiseOp::Or => lhs | rhs,
+            BitwiseOp::Xor => lhs ^ rhs,
+            BitwiseOp::Not => !lhs & mask,
+        }
+    }
+}
+
+/// A lookup table of `(tag, lhs, rhs, out)` for every `BitwiseOp` on `BITS`-bit operands.
+///
+///       tag   |  lhs  |  rhs  |  out
+///     ---------------------------------
+///        0    |   0   |   0   |   0      <- default row, matched by disabled lookups
+///      AND    |   0   |   0   |   0
+///      AND    |   0   |   1   |   0
+///      ...    |  ...  |  ...  |  ...
+///      NOT    | 2^B-1 |   0   |   0
+///
+/// The table has `3 * 4^BITS + 2^BITS + 1` rows.
+#[derive(Debug, Clone)]
+pub struct BitwiseTableConfig<F: FieldExt, const BITS: usize> {
+    pub tag: TableColumn,
+    pub lhs: TableColumn,
+    pub rhs: TableColumn,
+    pub out: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> BitwiseTableConfig<F, BITS> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            tag: meta.lookup_table_column(),
+            lhs: meta.lookup_table_column(),
+            rhs: meta.lookup_table_column(),
+            out: meta.lookup_table_column(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load bitwise table",
+            |mut table| {
+                let mut offset = 0;
+                let mut assign_row = |tag: u64, lhs: u64, rhs: u64, out: u64| {
+                    for (column, value) in [
+                        (self.tag, tag),
+                        (self.lhs, lhs),
+                        (self.rhs, rhs),
+                        (self.out, out),
+                    ] {
+                        table.assign_cell(
+                            || "bitwise",
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                    offset += 1;
+                    Ok::<_, Error>(())
+                };
+
+                assign_row(0, 0, 0, 0)?;
+                for op in BitwiseOp::ALL {
+                    for lhs in 0..1 << BITS {
+                        if op == BitwiseOp::Not {
+                            assign_row(op.tag(), lhs, 0, op.apply(lhs, 0, BITS))?;
+                            continue;
+                        }
+                        for rhs in 0..1 << BITS {
+                            assign_row(op.tag(), lhs, rhs, op.apply(lhs, rhs, BITS))?;
+                        }
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+/// This chip computes AND, OR, XOR and NOT of `BITS`-bit values, checking every result with
+/// one lookup into a shared table tagged by operation.
+///
+///       lhs   |  rhs  |  out  |  tag  |  q_lookup
+///     ----------------------------------------------
+///       a_0   |  b_0  |  c_0  |  AND  |     1
+///       a_1   |   0   |  c_1  |  NOT  |     1
+///
+/// Operands wider than `BITS` bits are rejected by the lookup.
+#[derive(Debug, Clone)]
+pub struct BitwiseConfig<F: FieldExt, const BITS: usize> {
+    lhs: Column<Advice>,
+    rhs: Column<Advice>,
+    out: Column<Advice>,
+    tag: Column<Fixed>,
+    q_lookup: Selector,
+    pub table: BitwiseTableConfig<F, BITS>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BitwiseChip<F: FieldExt, const BITS: usize> {
+    config: BitwiseConfig<F, BITS>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const BITS: usize> Chip<F> for BitwiseChip<F, BITS> {
+    type Config = BitwiseConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const BITS: usize> BitwiseChip<F, BITS> {
+    pub fn construct(config: BitwiseConfig<F, BITS>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        lhs: Column<Advice>,
+        rhs: Column<Advice>,
+        out: Column<Advice>,
+    ) -> BitwiseConfig<F, BITS> {
+        let tag = meta.fixed_column();
+        let q_lookup = meta.complex_selector();
+        let table = BitwiseTableConfig::configure(meta);
+
+        meta.enable_equality(lhs);
+        meta.enable_equality(rhs);
+        meta.enable_equality(out);
+
+        meta.lookup(|meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let tag = meta.query_fixed(tag, Rotation::cur());
+            let lhs = meta.query_advice(lhs, Rotation::cur());
+            let rhs = meta.query_advice(rhs, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            vec![
+                (q_lookup.clone() * tag, table.tag),
+                (q_lookup.clone() * lhs, table.lhs),
+                (q_lookup.clone() * rhs, table.rhs),
+                (q_lookup * out, table.out),
+            ]
+        });
+
+        BitwiseConfig {
+            lhs,
+            rhs,
+            out,
+            tag,
+            q_lookup,
+            table,
+        }
+    }
+
+    /// Returns `op(lhs, rhs)`. `BitwiseOp::Not` ignores `rhs` and is the same as `not`.
+    pub fn bitwise(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: BitwiseOp,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if op == BitwiseOp::Not {
+            return self.not(layouter, lhs);
+        }
+
+        layouter.assign_region(
+            || format!("{:?}", op),
+            |mut region| {
+                self.assign_row(&mut region, op)?;
+
+                lhs.copy_advice(|| "lhs", &mut region, self.config.lhs, 0)?;
+                rhs.copy_advice(|| "rhs", &mut region, self.config.rhs, 0)?;
+
+                let out = lhs
+                    .value()
+                    .zip(rhs.value())
+                    .map(|(lhs, rhs)| Self::apply(op, lhs, rhs));
+                region.assign_advice(|| "out", self.config.out, 0, || out)
+            },
+        )
+    }
+
+    /// Returns the bitwise complement of `value` within `BITS` bits.
+    pub fn not(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "Not",
+            |mut region| {
+                let op = BitwiseOp::Not;
+                self.assign_row(&mut region, op)?;
+
+                value.copy_advice(|| "lhs", &mut region, self.config.lhs, 0)?;
+                // Any other value misses every NOT row of the table.
+                region.assign_advice(
+                    || "rhs",
+                    self.config.rhs,
+                    0,
+                    || Value::known(F::zero()),
+                )?;
+
+                let out = value.value().map(|value| Self::apply(op, value, &F::zero()));
+                region.assign_advice(|| "out", self.config.out, 0, || out)
+            },
+        )
+    }
+
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        op: BitwiseOp,
+    ) -> Result<(), Error> {
+        self.config.q_lookup.enable(region, 0)?;
+        region.assign_fixed(
+            || "tag",
+            self.config.tag,
+            0,
+            || Value::known(F::from(op.tag())),
+        )?;
+        Ok(())
+    }
+
+    fn apply(op: BitwiseOp, lhs: &F, rhs: &F) -> F {
+        // Operands wider than `BITS` bits are truncated here and rejected by the lookup.
+        let lhs = lhs.get_lower_32() as u64;
+        let rhs = rhs.get_lower_32() as u64;
+        F::from(op.apply(lhs, rhs, BITS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::floor_planner::V1,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    const BITS: usize = 2;
+
+    #[derive(Debug, Clone)]
+    struct TestConfig {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        bitwise: BitwiseConfig<Fp, BITS>,
+    }
+
+    /// Applies each `(op, lhs, rhs)` and exposes the results in order.
+    #[derive(Default)]
+    struct MyCircuit {
+        ops: Vec<(BitwiseOp, u64, u64)>,
+    }
+
+    impl Circuit<Fp> for MyCircuit {
+        type Config = TestConfig;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                ops: self.ops.clone(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+
+            let [lhs, rhs, out] = [(); 3].map(|_| meta.advice_column());
+            let bitwise = BitwiseChip::configure(meta, lhs, rhs, out);
+
+            TestConfig {
+                input,
+                instance,
+                bitwise,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.bitwise.table.load(&mut layouter)?;
+            let chip = BitwiseChip::construct(config.bitwise);
+
+            for (row, (op, lhs, rhs)) in self.ops.iter().enumerate() {
+                // NOT with a zero `rhs` goes through `not`, any other op through `bitwise`.
+                let use_not = *op == BitwiseOp::Not && *rhs == 0;
+
+                let (lhs, rhs) = layouter.assign_region(
+                    || "operands",
+                    |mut region| {
+                        let lhs = region.assign_advice(
+                            || "lhs",
+                            config.input,
+                            0,
+                            || Value::known(Fp::from(*lhs)),
+                        )?;
+                        let rhs = region.assign_advice(
+                            || "rhs",
+                            config.input,
+                            1,
+                            || Value::known(Fp::from(*rhs)),
+                        )?;
+                        Ok((lhs, rhs))
+                    },
+                )?;
+
+                let out = if use_not {
+                    chip.not(layouter.namespace(|| "not"), &lhs)?
+                } else {
+                    chip.bitwise(layouter.namespace(|| "bitwise"), *op, &lhs, &rhs)?
+                };
+                layouter.constrain_instance(out.cell(), config.instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_bitwise_chip() {
+        let k = 8;
+
+        let mut ops = vec![];
+        for op in BitwiseOp::ALL {
+            for lhs in 0..1 << BITS {
+                for rhs in 0..1 << BITS {
+                    ops.push((op, lhs, rhs));
+                }
+            }
+        }
+        let outs: Vec<_> = ops
+            .iter()
+            .map(|(op, lhs, rhs)| Fp::from(op.apply(*lhs, *rhs, BITS)))
+            .collect();
+
+        let circuit = MyCircuit { ops };
+        let prover = MockProver::run(k, &circuit, vec![outs.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // A wrong claimed result breaks the copy to the instance.
+        let mut wrong = outs;
+        wrong[0] += Fp::one();
+        let prover = MockProver::run(k, &circuit, vec![wrong]).unwrap();
+        assert!(prover
+            .verify()
+            .unwrap_err()
+            .iter()
+            .all(|f| matches!(f, VerifyFailure::Permutation { .. })));
+    }
+
+    #[test]
+    fn test_bitwise_chip_rejects_wide_operands() {
+        let k = 7;
+
+        let circuit = MyCircuit {
+            ops: vec![(BitwiseOp::Xor, 1 << BITS, 1)],
+        };
+        let out = Fp::from(BitwiseOp::Xor.apply(1 << BITS, 1, BITS));
+        let prover = MockProver::run(k, &circuit, vec![vec![out]]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|f| matches!(f, VerifyFailure::Lookup { .. })));
+    }
+}
@@ -0,0 +1,481 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use crate::range_check::example1::RangeConstrained;
+
+/// A cell constrained to hold `0` or `1`.
+#[derive(Debug, Clone)]
+pub struct AssignedBit<F: FieldExt>(AssignedCell<F, F>);
+
+impl<F: FieldExt> AssignedBit<F> {
+    /// Returns the cell holding the bit.
+    pub fn cell(&self) -> &AssignedCell<F, F> {
+        &self.0
+    }
+
+    /// Returns the witnessed bit as a field element.
+    pub fn value(&self) -> Value<&F> {
+        self.0.value()
+    }
+}
+
+/// This chip assigns boolean-constrained cells and combines them with boolean gates.
+///
+///       a  |  b  |  out           |  selector
+///     -------------------------------------------
+///          |     |  bit           |  q_bool
+///       a  |  b  |  a * b         |  q_and
+///       a  |  b  |  a + b - a * b |  q_or
+///       a  |  b  |  a + b - 2ab   |  q_xor
+///       a  |     |  1 - a         |  q_not
+///
+/// The operands are `AssignedBit`s, so every output is boolean without a further check.
+/// `and_many` and `or_many` chain one row per operand, copying each `out` into the next `a`.
+#[derive(Debug, Clone)]
+pub struct BoolConfig {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    out: Column<Advice>,
+    q_bool: Selector,
+    q_and: Selector,
+    q_or: Selector,
+    q_xor: Selector,
+    q_not: Selector,
+}
+
+pub struct BoolChip<F: FieldExt> {
+    config: BoolConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for BoolChip<F> {
+    type Config = BoolConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    And,
+    Or,
+    Xor,
+}
+
+impl Op {
+    fn apply<F: FieldExt>(self, a: F, b: F) -> F {
+        match self {
+            Op::And => a * b,
+            Op::Or => a + b - a * b,
+            Op::Xor => a + b - (a * b).double(),
+        }
+    }
+
+    fn expr<F: FieldExt>(self, a: Expression<F>, b: Expression<F>) -> Expression<F> {
+        let ab = a.clone() * b.clone();
+        match self {
+            Op::And => ab,
+            Op::Or => a + b - ab,
+            Op::Xor => a + b - Expression::Constant(F::from(2)) * ab,
+        }
+    }
+}
+
+impl<F: FieldExt> BoolChip<F> {
+    pub fn construct(config: BoolConfig) -> Self {
+        BoolChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        out: Column<Advice>,
+    ) -> BoolConfig {
+        let q_bool = meta.selector();
+        let q_and = meta.selector();
+        let q_or = meta.selector();
+        let q_xor = meta.selector();
+        let q_not = meta.selector();
+
+        for column in [a, b, out] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("bit", |meta| {
+            let q = meta.query_selector(q_bool);
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![q * out.clone() * (Expression::Constant(F::one()) - out)]
+        });
+
+        for (name, selector, op) in [
+            ("and", q_and, Op::And),
+            ("or", q_or, Op::Or),
+            ("xor", q_xor, Op::Xor),
+        ] {
+            meta.create_gate(name, |meta| {
+                let q = meta.query_selector(selector);
+                let a = meta.query_advice(a, Rotation::cur());
+                let b = meta.query_advice(b, Rotation::cur());
+                let out = meta.query_advice(out, Rotation::cur());
+                vec![q * (out - op.expr(a, b))]
+            });
+        }
+
+        meta.create_gate("not", |meta| {
+            let q = meta.query_selector(q_not);
+            let a = meta.query_advice(a, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![q * (out - (Expression::Constant(F::one()) - a))]
+        });
+
+        BoolConfig {
+            a,
+            b,
+            out,
+            q_bool,
+            q_and,
+            q_or,
+            q_xor,
+            q_not,
+        }
+    }
+
+    /// Witnesses a new bit.
+    pub fn assign_bit(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<bool>,
+    ) -> Result<AssignedBit<F>, Error> {
+        layouter.assign_region(
+            || "bit",
+            |mut region| {
+                self.config.q_bool.enable(&mut region, 0)?;
+                region
+                    .assign_advice(|| "bit", self.config.out, 0, || value.map(F::from))
+                    .map(AssignedBit)
+            },
+        )
+    }
+
+    /// Constrains an existing cell, such as the output of `IsZeroWithOutputChip`, to be
+    /// boolean.
+    pub fn constrain_bit(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<AssignedBit<F>, Error> {
+        layouter.assign_region(
+            || "constrain bit",
+            |mut region| {
+                self.config.q_bool.enable(&mut region, 0)?;
+                cell.copy_advice(|| "bit", &mut region, self.config.out, 0)
+                    .map(AssignedBit)
+            },
+        )
+    }
+
+    /// Converts a value range-checked to `0..2` into a bit. The range check already makes it
+    /// boolean, so it is only copied; its column must have equality enabled.
+    pub fn from_range_constrained(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &RangeConstrained<F, 2>,
+    ) -> Result<AssignedBit<F>, Error> {
+        layouter.assign_region(
+            || "range constrained bit",
+            |mut region| {
+                let bit = region.assign_advice(
+                    || "bit",
+                    self.config.out,
+                    0,
+                    || value.value().map(|v| v.evaluate()),
+                )?;
+                region.constrain_equal(value.cell().cell(), bit.cell())?;
+                Ok(AssignedBit(bit))
+            },
+        )
+    }
+
+    pub fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedBit<F>,
+        b: &AssignedBit<F>,
+    ) -> Result<AssignedBit<F>, Error> {
+        self.fold(layouter, Op::And, &[a.clone(), b.clone()])
+    }
+
+    pub fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedBit<F>,
+        b: &AssignedBit<F>,
+    ) -> Result<AssignedBit<F>, Error> {
+        self.fold(layouter, Op::Or, &[a.clone(), b.clone()])
+    }
+
+    pub fn xor(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedBit<F>,
+        b: &AssignedBit<F>,
+    ) -> Result<AssignedBit<F>, Error> {
+        self.fold(layouter, Op::Xor, &[a.clone(), b.clone()])
+    }
+
+    pub fn not(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedBit<F>,
+    ) -> Result<AssignedBit<F>, Error> {
+        layouter.assign_region(
+            || "not",
+            |mut region| {
+                self.config.q_not.enable(&mut region, 0)?;
+                a.0.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                let out = a.value().map(|a| F::one() - a);
+                region
+                    .assign_advice(|| "out", self.config.out, 0, || out)
+                    .map(AssignedBit)
+            },
+        )
+    }
+
+    /// Returns the AND of one or more bits.
+    pub fn and_many(
+        &self,
+        layouter: impl Layouter<F>,
+        bits: &[AssignedBit<F>],
+    ) -> Result<AssignedBit<F>, Error> {
+        self.fold(layouter, Op::And, bits)
+    }
+
+    /// Returns the OR of one or more bits.
+    pub fn or_many(
+        &self,
+        layouter: impl Layouter<F>,
+        bits: &[AssignedBit<F>],
+    ) -> Result<AssignedBit<F>, Error> {
+        self.fold(layouter, Op::Or, bits)
+    }
+
+    fn fold(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: Op,
+        bits: &[AssignedBit<F>],
+    ) -> Result<AssignedBit<F>, Error> {
+        let (first, rest) = bits.split_first().ok_or(Error::Synthesis)?;
+        let selector = match op {
+            Op::And => self.config.q_and,
+            Op::Or => self.config.q_or,
+            Op::Xor => self.config.q_xor,
+        };
+
+        layouter.assign_region(
+            || "fold bits",
+            |mut region| {
+                let mut acc = first.clone();
+
+                for (row, bit) in rest.iter().enumerate() {
+                    selector.enable(&mut region, row)?;
+
+                    let a = acc.0.copy_advice(|| "a", &mut region, self.config.a, row)?;
+                    let b = bit.0.copy_advice(|| "b", &mut region, self.config.b, row)?;
+
+                    let out = a.value().zip(b.value()).map(|(a, b)| op.apply(*a, *b));
+                    acc = region
+                        .assign_advice(|| "out", self.config.out, row, || out)
+                        .map(AssignedBit)?;
+                }
+
+                Ok(acc)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::is_equal::{IsEqualChip, IsEqualConfig};
+    use crate::range_check::example1::RangeCheckConfig;
+    use halo2_proofs::{
+        circuit::floor_planner::V1,
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Assigned, Circuit, Instance},
+    };
+
+    #[derive(Debug, Clone)]
+    struct TestConfig<F: FieldExt> {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        boolean: BoolConfig,
+        is_equal: IsEqualConfig<F>,
+        range_check: RangeCheckConfig<F, 2>,
+    }
+
+    /// Exposes, in order, `x & y`, `x | y`, `x ^ y`, `!x`, then the AND and OR of
+    /// `[x, y, lhs == rhs, z]`, where `z` is range-checked to `0..2`.
+    ///
+    /// `raw` is constrained to be a bit and exposed last.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        x: Value<bool>,
+        y: Value<bool>,
+        lhs: Value<F>,
+        rhs: Value<F>,
+        z: Value<F>,
+        raw: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = TestConfig<F>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+
+            let [a, b, out, value_inv] = [(); 4].map(|_| meta.advice_column());
+            let boolean = BoolChip::configure(meta, a, b, out);
+            let is_equal = IsEqualChip::configure(meta, a, b, value_inv, out);
+
+            let z = meta.advice_column();
+            meta.enable_equality(z);
+            let range_check = RangeCheckConfig::configure(meta, z);
+
+            TestConfig {
+                input,
+                instance,
+                boolean,
+                is_equal,
+                range_check,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = BoolChip::construct(config.boolean);
+            let is_equal = IsEqualChip::construct(config.is_equal);
+
+            let x = chip.assign_bit(layouter.namespace(|| "x"), self.x)?;
+            let y = chip.assign_bit(layouter.namespace(|| "y"), self.y)?;
+
+            let (lhs, rhs, raw) = layouter.assign_region(
+                || "inputs",
+                |mut region| {
+                    let lhs = region.assign_advice(|| "lhs", config.input, 0, || self.lhs)?;
+                    let rhs = region.assign_advice(|| "rhs", config.input, 1, || self.rhs)?;
+                    let raw = region.assign_advice(|| "raw", config.input, 2, || self.raw)?;
+                    Ok((lhs, rhs, raw))
+                },
+            )?;
+            let eq = is_equal.assign(layouter.namespace(|| "lhs == rhs"), &lhs, &rhs)?;
+            let eq = chip.constrain_bit(layouter.namespace(|| "eq"), &eq)?;
+
+            let z = config
+                .range_check
+                .assign(layouter.namespace(|| "z"), self.z.map(Assigned::from))?;
+            let z = chip.from_range_constrained(layouter.namespace(|| "z bit"), &z)?;
+
+            let raw = chip.constrain_bit(layouter.namespace(|| "raw"), &raw)?;
+
+            let all = [x.clone(), y.clone(), eq, z];
+            let outputs = [
+                chip.and(layouter.namespace(|| "and"), &x, &y)?,
+                chip.or(layouter.namespace(|| "or"), &x, &y)?,
+                chip.xor(layouter.namespace(|| "xor"), &x, &y)?,
+                chip.not(layouter.namespace(|| "not"), &x)?,
+                chip.and_many(layouter.namespace(|| "and many"), &all)?,
+                chip.or_many(layouter.namespace(|| "or many"), &all)?,
+                raw,
+            ];
+            for (row, bit) in outputs.iter().enumerate() {
+                layouter.constrain_instance(bit.cell().cell(), config.instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn circuit(x: bool, y: bool, eq: bool, z: bool, raw: u64) -> MyCircuit<Fp> {
+        MyCircuit {
+            x: Value::known(x),
+            y: Value::known(y),
+            lhs: Value::known(Fp::from(7)),
+            rhs: Value::known(Fp::from(if eq { 7 } else { 8 })),
+            z: Value::known(Fp::from(z)),
+            raw: Value::known(Fp::from(raw)),
+        }
+    }
+
+    fn expected(x: bool, y: bool, eq: bool, z: bool, raw: u64) -> Vec<Fp> {
+        [x & y, x | y, x ^ y, !x, x & y & eq & z, x | y | eq | z]
+            .into_iter()
+            .map(Fp::from)
+            .chain([Fp::from(raw)])
+            .collect()
+    }
+
+    #[test]
+    fn test_bool_chip() {
+        let k = 5;
+
+        for bits in 0..16u8 {
+            let [x, y, eq, z] = [0, 1, 2, 3].map(|i| (bits >> i) & 1 == 1);
+            let raw = (bits % 2) as u64;
+
+            let prover = MockProver::run(
+                k,
+                &circuit(x, y, eq, z, raw),
+                vec![expected(x, y, eq, z, raw)],
+            )
+            .unwrap();
+            prover.assert_satisfied();
+
+            // Any wrong result is caught.
+            for row in 0..7 {
+                let mut wrong = expected(x, y, eq, z, raw);
+                wrong[row] = Fp::one() - wrong[row];
+                let prover = MockProver::run(k, &circuit(x, y, eq, z, raw), vec![wrong]).unwrap();
+                assert!(prover.verify().is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_bool_chip_rejects_non_boolean() {
+        let k = 5;
+        let circuit = circuit(true, false, true, true, 2);
+        let prover =
+            MockProver::run(k, &circuit, vec![expected(true, false, true, true, 2)]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -1,4 +1,123 @@
-mod example1;
-mod example2;
-mod example3;
-mod example4;
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*};
+
+pub mod doubling;
+pub mod example1;
+pub mod example2;
+pub mod example3;
+pub mod example4;
+pub mod recurrence;
+pub mod variable_length;
+
+/// Instructions shared by the Fibonacci chips.
+///
+/// Circuits written against this trait can switch between the three-column, single-column
+/// and add/xor layouts without touching their `synthesize` code.
+pub trait FibonacciInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a term of the sequence.
+    type Num;
+
+    /// Loads any lookup tables the chip relies on.
+    fn load(&self, _layouter: impl Layouter<F>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Assigns the first `nterms` terms, taking f(0) and f(1) from `seeds`, and returns the
+    /// last term.
+    ///
+    /// `nterms` must be at least 3, since every layout derives f(2) from the seeds; smaller
+    /// values fail with `Error::Synthesis`.
+    fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        seeds: &Seeds<F>,
+        nterms: usize,
+    ) -> Result<Self::Num, Error>;
+
+    /// Constrains `num` to equal the public input at `row`.
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        num: &Self::Num,
+        row: usize,
+    ) -> Result<(), Error>;
+}
+
+/// Row of the instance column holding the claimed F(n) when the seeds are public; rows 0
+/// and 1 hold f(0) and f(1).
+pub const OUTPUT_ROW: usize = 2;
+
+/// Where a Fibonacci chip takes f(0) and f(1) from.
+#[derive(Clone, Copy, Debug)]
+pub enum Seeds<F> {
+    /// Read from instance rows 0 and 1, with F(n) exposed at `OUTPUT_ROW`.
+    Public,
+    /// Private witnesses, with only F(n) exposed, at instance row 0.
+    ///
+    /// `n` is not an instance value: it fixes the shape of the circuit and so is already
+    /// bound by the verifying key.
+    Private(Value<F>, Value<F>),
+}
+
+impl<F> Default for Seeds<F> {
+    fn default() -> Self {
+        Seeds::Public
+    }
+}
+
+impl<F: FieldExt> Seeds<F> {
+    /// Returns the same kind of seeds with the private values removed.
+    pub fn without_witnesses(&self) -> Self {
+        match self {
+            Seeds::Public => Seeds::Public,
+            Seeds::Private(_, _) => Seeds::Private(Value::unknown(), Value::unknown()),
+        }
+    }
+
+    /// Returns the instance row at which F(n) is exposed.
+    pub fn output_row(&self) -> usize {
+        match self {
+            Seeds::Public => OUTPUT_ROW,
+            Seeds::Private(_, _) => 0,
+        }
+    }
+
+    /// Assigns f(`index`), for `index` 0 or 1, to `column` at `offset`.
+    pub(crate) fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        instance: Column<Instance>,
+        index: usize,
+        column: Column<Advice>,
+        offset: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(index < 2);
+
+        match self {
+            Seeds::Public => region.assign_advice_from_instance(
+                || format!("f({})", index),
+                instance,
+                index,
+                column,
+                offset,
+            ),
+            Seeds::Private(f0, f1) => region.assign_advice(
+                || format!("f({})", index),
+                column,
+                offset,
+                || if index == 0 { *f0 } else { *f1 },
+            ),
+        }
+    }
+}
+
+/// Computes F(n) natively from the seeds f(0) and f(1).
+#[cfg(test)]
+pub(crate) fn fibonacci<F: FieldExt>(f0: F, f1: F, n: usize) -> F {
+    let (mut a, mut b) = (f0, f1);
+    for _ in 0..n {
+        let c = a + b;
+        a = b;
+        b = c;
+    }
+    a
+}
@@ -0,0 +1,298 @@
+use std::marker::PhantomData;
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+use crate::util::min_k;
+
+/// Index of the instance row holding `n`.
+pub const N_ROW: usize = 0;
+/// Index of the instance row holding F(n).
+pub const OUTPUT_ROW: usize = 1;
+
+#[derive(Debug, Clone)]
+pub struct DoublingConfig {
+    bit: Column<Advice>,
+    a: Column<Advice>,
+    b: Column<Advice>,
+    acc: Column<Advice>,
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+/// Proves F(n) of the standard sequence, F(0) = 0 and F(1) = 1, in `NUM_BITS + 1` rows.
+///
+/// Each row holds `(F(k), F(k+1))` together with the prefix `k` of `n` read so far, and the
+/// next bit of `n`, most significant first. The doubling identities
+///
+///     F(2k)   = F(k) * (2 F(k+1) - F(k))
+///     F(2k+1) = F(k)^2 + F(k+1)^2
+///
+/// give `(F(2k), F(2k+1))` for a zero bit and `(F(2k+1), F(2k+2))` for a one bit.
+///
+///     bit  |   a    |    b     |  acc  | selector
+///     b_0  |   0    |    1     |   0   |    1
+///     b_1  | F(k_1) | F(k_1+1) |  k_1  |    1
+///     ...  |  ...   |   ...    |  ...  |   ...
+///          |  F(n)  |  F(n+1)  |   n   |    0
+#[derive(Debug, Clone)]
+pub struct DoublingChip<F: FieldExt, const NUM_BITS: usize> {
+    config: DoublingConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const NUM_BITS: usize> DoublingChip<F, NUM_BITS> {
+    pub fn construct(config: DoublingConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> DoublingConfig {
+        let bit = meta.advice_column();
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let acc = meta.advice_column();
+        let constants = meta.fixed_column();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+        meta.enable_constant(constants);
+
+        meta.create_gate("doubling", |meta| {
+            let s = meta.query_selector(selector);
+            let a_next = meta.query_advice(a, Rotation::next());
+            let b_next = meta.query_advice(b, Rotation::next());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+
+            let one = Expression::Constant(F::one());
+            let two = Expression::Constant(F::from(2));
+
+            // F(2k) and F(2k+1)
+            let even = a.clone() * (b.clone() * two.clone() - a.clone());
+            let odd = a.clone() * a + b.clone() * b;
+
+            Constraints::with_selector(
+                s,
+                [
+                    ("bit is boolean", bit.clone() * (one - bit.clone())),
+                    (
+                        "next F(k)",
+                        a_next - (even.clone() + bit.clone() * (odd.clone() - even.clone())),
+                    ),
+                    ("next F(k+1)", b_next - (odd + bit.clone() * even)),
+                    ("next k", acc_next - (acc * two + bit)),
+                ],
+            )
+        });
+
+        DoublingConfig {
+            bit,
+            a,
+            b,
+            acc,
+            selector,
+            instance,
+        }
+    }
+
+    /// Assigns the doubling steps for the bits of `n`, and returns the cells holding `n`
+    /// and F(n).
+    ///
+    /// `n` must be below `2^NUM_BITS`, otherwise no bits recompose to it.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        n: Value<u64>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "doubling",
+            |mut region| {
+                let mut a = region.assign_advice_from_constant(
+                    || "F(0)",
+                    self.config.a,
+                    0,
+                    F::zero(),
+                )?;
+                let mut b = region.assign_advice_from_constant(
+                    || "F(1)",
+                    self.config.b,
+                    0,
+                    F::one(),
+                )?;
+                let mut acc = region.assign_advice_from_constant(
+                    || "k",
+                    self.config.acc,
+                    0,
+                    F::zero(),
+                )?;
+
+                for row in 0..NUM_BITS {
+                    self.config.selector.enable(&mut region, row)?;
+
+                    let bit = n.map(|n| (n >> (NUM_BITS - 1 - row)) & 1);
+                    region.assign_advice(|| "bit", self.config.bit, row, || bit.map(F::from))?;
+
+                    let next = a.value().zip(b.value()).zip(bit).map(|((a, b), bit)| {
+                        let even = *a * (b.double() - a);
+                        let odd = a.square() + b.square();
+                        if bit == 1 {
+                            (odd, even + odd)
+                        } else {
+                            (even, odd)
+                        }
+                    });
+
+                    let next_acc = acc
+                        .value()
+                        .zip(bit)
+                        .map(|(acc, bit)| acc.double() + F::from(bit));
+
+                    a = region.assign_advice(
+                        || "F(k)",
+                        self.config.a,
+                        row + 1,
+                        || next.map(|(a, _)| a),
+                    )?;
+                    b = region.assign_advice(
+                        || "F(k+1)",
+                        self.config.b,
+                        row + 1,
+                        || next.map(|(_, b)| b),
+                    )?;
+                    acc = region.assign_advice(|| "k", self.config.acc, row + 1, || next_acc)?;
+                }
+
+                Ok((acc, a))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const NUM_BITS: usize> Chip<F> for DoublingChip<F, NUM_BITS> {
+    type Config = DoublingConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+/// Proves F(n) for a public `n < 2^NUM_BITS`, with `n` at instance row `N_ROW` and F(n) at
+/// `OUTPUT_ROW`.
+#[derive(Default)]
+pub struct MyCircuit<F, const NUM_BITS: usize> {
+    n: Value<u64>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const NUM_BITS: usize> MyCircuit<F, NUM_BITS> {
+    pub fn new(n: u64) -> Self {
+        Self {
+            n: Value::known(n),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the smallest `k` that fits the circuit.
+    pub fn k() -> u32 {
+        // One row per bit, plus the final row.
+        min_k::<F, Self>(NUM_BITS + 1)
+    }
+}
+
+impl<F: FieldExt, const NUM_BITS: usize> Circuit<F> for MyCircuit<F, NUM_BITS> {
+    type Config = DoublingConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        DoublingChip::<F, NUM_BITS>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = DoublingChip::<F, NUM_BITS>::construct(config);
+
+        let (n, out) = chip.assign(layouter.namespace(|| "doubling"), self.n)?;
+        chip.expose_public(layouter.namespace(|| "n"), &n, N_ROW)?;
+        chip.expose_public(layouter.namespace(|| "out"), &out, OUTPUT_ROW)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MyCircuit;
+    use crate::fibonacci::{example1, fibonacci};
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    type DoublingCircuit = MyCircuit<Fp, 32>;
+
+    fn run(n: usize, out: Fp) -> MockProver<Fp> {
+        let circuit = DoublingCircuit::new(n as u64);
+        let public_input = vec![Fp::from(n as u64), out];
+        MockProver::run(DoublingCircuit::k(), &circuit, vec![public_input]).unwrap()
+    }
+
+    #[test]
+    fn fibonacci_doubling() {
+        assert_eq!(DoublingCircuit::k(), 6);
+
+        for n in 0..=20 {
+            let out = fibonacci(Fp::zero(), Fp::one(), n);
+
+            run(n, out).assert_satisfied();
+            assert!(run(n, out + Fp::one()).verify().is_err());
+        }
+    }
+
+    #[test]
+    fn fibonacci_doubling_matches_example1() {
+        // The linear circuit exposes the same output for the seeds 0 and 1.
+        for n in [2, 3, 9, 20, 100] {
+            let out = fibonacci(Fp::zero(), Fp::one(), n);
+
+            let linear = example1::MyCircuit::new(n);
+            let public_input = vec![Fp::zero(), Fp::one(), out];
+            let prover = MockProver::run(linear.k(), &linear, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+
+            run(n, out).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn fibonacci_doubling_large_n() {
+        // The linear circuits would need k = 20 for this.
+        let n = 1_000_000;
+        run(n, fibonacci(Fp::zero(), Fp::one(), n)).assert_satisfied();
+    }
+}
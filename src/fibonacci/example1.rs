@@ -1,8 +1,11 @@
 use std::marker::PhantomData;
 use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
 
+use super::{FibonacciInstructions, Seeds};
+use crate::util::min_k;
+
 #[derive(Debug, Clone)]
-struct FibonacciConfig {
+pub struct FibonacciConfig {
     pub col_a: Column<Advice>,
     pub col_b: Column<Advice>,
     pub col_c: Column<Advice>,
@@ -11,7 +14,7 @@ struct FibonacciConfig {
 }
 
 #[derive(Debug, Clone)]
-struct FibonacciChip<F: FieldExt> {
+pub struct FibonacciChip<F: FieldExt> {
     config: FibonacciConfig,
     _marker: PhantomData<F>,
 }
@@ -61,21 +64,22 @@ impl<F: FieldExt> FibonacciChip<F> {
     pub fn assign_first_row(
         &self,
         mut layouter: impl Layouter<F>,
+        seeds: &Seeds<F>,
     ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
         layouter.assign_region(
             || "first row",
             |mut region| {
                 self.config.selector.enable(&mut region, 0)?;
 
-                let a_cell = region.assign_advice_from_instance(
-                    || "f(0)",
+                let a_cell = seeds.assign(
+                    &mut region,
                     self.config.instance,
                     0,
                     self.config.col_a,
                     0)?;
 
-                let b_cell = region.assign_advice_from_instance(
-                    || "f(1)",
+                let b_cell = seeds.assign(
+                    &mut region,
                     self.config.instance,
                     1,
                     self.config.col_b,
@@ -129,26 +133,97 @@ impl<F: FieldExt> FibonacciChip<F> {
             },
         )
     }
+}
+
+impl<F: FieldExt> Chip<F> for FibonacciChip<F> {
+    type Config = FibonacciConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
 
-    pub fn expose_public(
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> FibonacciInstructions<F> for FibonacciChip<F> {
+    type Num = AssignedCell<F, F>;
+
+    fn assign(
         &self,
         mut layouter: impl Layouter<F>,
-        cell: &AssignedCell<F, F>,
+        seeds: &Seeds<F>,
+        nterms: usize,
+    ) -> Result<Self::Num, Error> {
+        if nterms < 3 {
+            return Err(Error::Synthesis);
+        }
+
+        let (_, mut prev_b, mut prev_c) =
+            self.assign_first_row(layouter.namespace(|| "first row"), seeds)?;
+
+        for _i in 3..nterms {
+            let c_cell = self.assign_row(layouter.namespace(|| "next row"), &prev_b, &prev_c)?;
+            prev_b = prev_c;
+            prev_c = c_cell;
+        }
+
+        Ok(prev_c)
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: &Self::Num,
         row: usize,
     ) -> Result<(), Error> {
-        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+        layouter.constrain_instance(num.cell(), self.config.instance, row)
     }
 }
 
+/// Proves F(n) for the sequence seeded by f(0) and f(1), either public or private.
 #[derive(Default)]
-struct MyCircuit<F>(PhantomData<F>);
+pub struct MyCircuit<F> {
+    n: usize,
+    seeds: Seeds<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> MyCircuit<F> {
+    /// Seeds are read from instance rows 0 and 1.
+    pub fn new(n: usize) -> Self {
+        Self::with_seeds(n, Seeds::Public)
+    }
+
+    /// Seeds are private witnesses and only F(n) is public.
+    pub fn with_private_seeds(n: usize, f0: Value<F>, f1: Value<F>) -> Self {
+        Self::with_seeds(n, Seeds::Private(f0, f1))
+    }
+
+    fn with_seeds(n: usize, seeds: Seeds<F>) -> Self {
+        assert!(n >= 2, "the first row already holds f(0), f(1) and f(2)");
+        Self {
+            n,
+            seeds,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the smallest `k` that fits the circuit.
+    pub fn k(&self) -> u32 {
+        // The first row holds f(0), f(1) and f(2), each further row adds one term.
+        min_k::<F, Self>(self.n - 1)
+    }
+}
 
 impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     type Config = FibonacciConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self::with_seeds(self.n, self.seeds.without_witnesses())
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -162,16 +237,9 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     ) -> Result<(), Error> {
         let chip = FibonacciChip::construct(config);
 
-        let (_, mut prev_b, mut prev_c) =
-            chip.assign_first_row(layouter.namespace(|| "first row"))?;
-
-        for _i in 3..10 {
-            let c_cell = chip.assign_row(layouter.namespace(|| "next row"), &prev_b, &prev_c)?;
-            prev_b = prev_c;
-            prev_c = c_cell;
-        }
+        let out_cell = chip.assign(layouter.namespace(|| "entire table"), &self.seeds, self.n + 1)?;
 
-        chip.expose_public(layouter.namespace(|| "out"), &prev_c, 2)?;
+        chip.expose_public(layouter.namespace(|| "out"), &out_cell, self.seeds.output_row())?;
 
         Ok(())
     }
@@ -179,20 +247,19 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
 
 #[cfg(test)]
 mod tests {
-    use std::marker::PhantomData;
-
     use super::MyCircuit;
-    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use crate::fibonacci::fibonacci;
+    use halo2_proofs::{circuit::Value, dev::MockProver, pasta::Fp};
 
     #[test]
     fn fibonacci_example1() {
-        let k = 4;
-
         let a = Fp::from(1); // F[0]
         let b = Fp::from(1); // F[1]
         let out = Fp::from(55); // F[9]
 
-        let circuit = MyCircuit(PhantomData);
+        let circuit = MyCircuit::new(9);
+        let k = circuit.k();
+        assert_eq!(k, 4);
 
         let mut public_input = vec![a, b, out];
 
@@ -205,6 +272,35 @@ mod tests {
         // _prover.assert_satisfied();
     }
 
+    #[test]
+    fn fibonacci_example1_any_n() {
+        let (a, b) = (Fp::from(1), Fp::from(1));
+
+        for n in [2, 3, 20, 100] {
+            let circuit = MyCircuit::new(n);
+            let public_input = vec![a, b, fibonacci(a, b, n)];
+
+            let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn fibonacci_example1_private_seeds() {
+        let (a, b) = (Fp::from(3), Fp::from(4));
+        let out = fibonacci(a, b, 9);
+
+        // Only F(9) is public; the prover knows a starting pair that reaches it.
+        let circuit = MyCircuit::with_private_seeds(9, Value::known(a), Value::known(b));
+        let prover = MockProver::run(circuit.k(), &circuit, vec![vec![out]]).unwrap();
+        prover.assert_satisfied();
+
+        // A pair that does not reach F(9) is rejected.
+        let circuit = MyCircuit::with_private_seeds(9, Value::known(b), Value::known(a));
+        let prover = MockProver::run(circuit.k(), &circuit, vec![vec![out]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn plot_fibonacci1() {
@@ -214,9 +310,9 @@ mod tests {
         root.fill(&WHITE).unwrap();
         let root = root.titled("Fib 1 Layout", ("sans-serif", 60)).unwrap();
 
-        let circuit = MyCircuit::<Fp>(PhantomData);
+        let circuit = MyCircuit::<Fp>::new(9);
         halo2_proofs::dev::CircuitLayout::default()
-            .render(4, &circuit, &root)
+            .render(circuit.k(), &circuit, &root)
             .unwrap();
     }
 }
@@ -1,18 +1,21 @@
 use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
 use std::marker::PhantomData;
 
+use super::{FibonacciInstructions, Seeds};
+use crate::util::min_k;
+
 #[derive(Debug, Clone)]
 struct ACell<F: FieldExt>(AssignedCell<F, F>);
 
 #[derive(Debug, Clone)]
-struct FibonacciConfig {
+pub struct FibonacciConfig {
     advice: Column<Advice>,
     selector: Selector,
     instance: Column<Instance>,
 }
 
 #[derive(Debug, Clone)]
-struct FibonacciChip<F: FieldExt> {
+pub struct FibonacciChip<F: FieldExt> {
     config: FibonacciConfig,
     _marker: PhantomData<F>,
 }
@@ -55,38 +58,58 @@ impl<F: FieldExt> FibonacciChip<F> {
             instance,
         }
     }
+}
+
+impl<F: FieldExt> Chip<F> for FibonacciChip<F> {
+    type Config = FibonacciConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
 
-    pub fn assign(
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> FibonacciInstructions<F> for FibonacciChip<F> {
+    type Num = AssignedCell<F, F>;
+
+    fn assign(
         &self,
         mut layouter: impl Layouter<F>,
-        nrows: usize,
-    ) -> Result<AssignedCell<F, F>, Error> {
+        seeds: &Seeds<F>,
+        nterms: usize,
+    ) -> Result<Self::Num, Error> {
+        if nterms < 3 {
+            return Err(Error::Synthesis);
+        }
+
         layouter.assign_region(
             || "entire fibonacci table",
             |mut region| {
-                self.config.selector.enable(&mut region, 0)?;
-                self.config.selector.enable(&mut region, 1)?;
+                // The gate at each selector row checks the term two rows below it.
+                for row in 0..nterms - 2 {
+                    self.config.selector.enable(&mut region, row)?;
+                }
 
-                let mut a_cell = region.assign_advice_from_instance(
-                    || "1",
+                let mut a_cell = seeds.assign(
+                    &mut region,
                     self.config.instance,
                     0,
                     self.config.advice,
                     0,
                 )?;
-                let mut b_cell = region.assign_advice_from_instance(
-                    || "1",
+                let mut b_cell = seeds.assign(
+                    &mut region,
                     self.config.instance,
                     1,
                     self.config.advice,
                     1,
                 )?;
 
-                for row in 2..nrows {
-                    if row < nrows - 2 {
-                        self.config.selector.enable(&mut region, row)?;
-                    }
-
+                for row in 2..nterms {
                     let c_cell = region.assign_advice(
                         || "advice",
                         self.config.advice,
@@ -103,25 +126,57 @@ impl<F: FieldExt> FibonacciChip<F> {
         )
     }
 
-    pub fn expose_public(
+    fn expose_public(
         &self,
         mut layouter: impl Layouter<F>,
-        cell: AssignedCell<F, F>,
+        num: &Self::Num,
         row: usize,
     ) -> Result<(), Error> {
-        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+        layouter.constrain_instance(num.cell(), self.config.instance, row)
     }
 }
 
+/// Proves F(n) for the sequence seeded by f(0) and f(1), either public or private.
 #[derive(Default)]
-struct MyCircuit<F>(PhantomData<F>);
+pub struct MyCircuit<F> {
+    n: usize,
+    seeds: Seeds<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> MyCircuit<F> {
+    /// Seeds are read from instance rows 0 and 1.
+    pub fn new(n: usize) -> Self {
+        Self::with_seeds(n, Seeds::Public)
+    }
+
+    /// Seeds are private witnesses and only the output is public.
+    pub fn with_private_seeds(n: usize, f0: Value<F>, f1: Value<F>) -> Self {
+        Self::with_seeds(n, Seeds::Private(f0, f1))
+    }
+
+    fn with_seeds(n: usize, seeds: Seeds<F>) -> Self {
+        assert!(n >= 2, "the gate needs at least three terms");
+        Self {
+            n,
+            seeds,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the smallest `k` that fits the circuit.
+    pub fn k(&self) -> u32 {
+        // One row per term, f(0) through f(n).
+        min_k::<F, Self>(self.n + 1)
+    }
+}
 
 impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     type Config = FibonacciConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self::with_seeds(self.n, self.seeds.without_witnesses())
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -137,9 +192,9 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     ) -> Result<(), Error> {
         let chip = FibonacciChip::construct(config);
 
-        let out_cell = chip.assign(layouter.namespace(|| "entire table"), 10)?;
+        let out_cell = chip.assign(layouter.namespace(|| "entire table"), &self.seeds, self.n + 1)?;
 
-        chip.expose_public(layouter.namespace(|| "out"), out_cell, 2)?;
+        chip.expose_public(layouter.namespace(|| "out"), &out_cell, self.seeds.output_row())?;
 
         Ok(())
     }
@@ -148,18 +203,18 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
 #[cfg(test)]
 mod tests {
     use super::MyCircuit;
-    use std::marker::PhantomData;
-    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use crate::fibonacci::fibonacci;
+    use halo2_proofs::{circuit::Value, dev::MockProver, pasta::Fp};
 
     #[test]
     fn fibonacci_example2() {
-        let k = 4;
-
         let a = Fp::from(1); // F[0]
         let b = Fp::from(1); // F[1]
         let out = Fp::from(55); // F[9]
 
-        let circuit = MyCircuit(PhantomData);
+        let circuit = MyCircuit::new(9);
+        let k = circuit.k();
+        assert_eq!(k, 4);
 
         let mut public_input = vec![a, b, out];
 
@@ -172,6 +227,33 @@ mod tests {
         // _prover.assert_satisfied();
     }
 
+    #[test]
+    fn fibonacci_example2_any_n() {
+        let (a, b) = (Fp::from(1), Fp::from(1));
+
+        for n in [2, 3, 20, 100] {
+            let circuit = MyCircuit::new(n);
+            let public_input = vec![a, b, fibonacci(a, b, n)];
+
+            let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn fibonacci_example2_private_seeds() {
+        let (a, b) = (Fp::from(3), Fp::from(4));
+        let out = fibonacci(a, b, 9);
+
+        let circuit = MyCircuit::with_private_seeds(9, Value::known(a), Value::known(b));
+        let prover = MockProver::run(circuit.k(), &circuit, vec![vec![out]]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = MyCircuit::with_private_seeds(9, Value::known(a), Value::known(a));
+        let prover = MockProver::run(circuit.k(), &circuit, vec![vec![out]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn plot_fibo2() {
@@ -180,9 +262,9 @@ mod tests {
         root.fill(&WHITE).unwrap();
         let root = root.titled("Fib 2 Layout", ("sans-serif", 60)).unwrap();
 
-        let circuit = MyCircuit::<Fp>(PhantomData);
+        let circuit = MyCircuit::<Fp>::new(9);
         halo2_proofs::dev::CircuitLayout::default()
-            .render(4, &circuit, &root)
+            .render(circuit.k(), &circuit, &root)
             .unwrap();
     }
 }
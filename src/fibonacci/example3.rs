@@ -1,23 +1,32 @@
-use crate::is_zero::{IsZeroChip, IsZeroConfig};
+use crate::is_zero::{IsZeroWithOutputChip, IsZeroWithOutputConfig};
+use crate::select::{SelectChip, SelectConfig};
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Selector},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
     poly::Rotation,
 };
 
+/// `f(a, b, c) = if a == b {c} else {a - b}`, built from the is-zero and select gadgets: the
+/// first region witnesses `a - b` and whether it is zero, then `SelectChip` picks `c` or
+/// `a - b`.
+///
+///       a  |  b  |  c  |  diff  |  value_inv  |  a_equals_b  |  selector
+///     -----------------------------------------------------------------------
+///       a  |  b  |  c  |  a - b |  1/(a - b)  |  a - b == 0  |     1
 #[derive(Debug, Clone)]
-struct FunctionConfig<F: FieldExt> {
+pub struct FunctionConfig<F: FieldExt> {
     selector: Selector,
     a: Column<Advice>,
     b: Column<Advice>,
     c: Column<Advice>,
-    a_equals_b: IsZeroConfig<F>,
-    output: Column<Advice>,
+    diff: Column<Advice>,
+    a_equals_b: IsZeroWithOutputConfig<F>,
+    select: SelectConfig,
 }
 
 #[derive(Debug, Clone)]
-struct FunctionChip<F: FieldExt> {
+pub struct FunctionChip<F: FieldExt> {
     config: FunctionConfig<F>,
 }
 
@@ -31,35 +40,37 @@ impl<F: FieldExt> FunctionChip<F> {
         let a = meta.advice_column();
         let b = meta.advice_column();
         let c = meta.advice_column();
+        let diff = meta.advice_column();
         let output = meta.advice_column();
 
+        meta.create_gate("diff = a - b", |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let diff = meta.query_advice(diff, Rotation::cur());
+            vec![s * (diff - (a - b))]
+        });
+
         let is_zero_advice_column = meta.advice_column();
-        let a_equals_b = IsZeroChip::configure(
+        let is_zero_output_column = meta.advice_column();
+        let a_equals_b = IsZeroWithOutputChip::configure(
             meta,
             |meta| meta.query_selector(selector),
-            |meta| meta.query_advice(a, Rotation::cur()) - meta.query_advice(b, Rotation::cur()),
+            |meta| meta.query_advice(diff, Rotation::cur()),
             is_zero_advice_column,
+            is_zero_output_column,
         );
 
-        meta.create_gate("f(a, b, c) = if a == b {c} else {a - b}", |meta| {
-            let s = meta.query_selector(selector);
-            let a = meta.query_advice(a, Rotation::cur());
-            let b = meta.query_advice(b, Rotation::cur());
-            let c = meta.query_advice(c, Rotation::cur());
-            let output = meta.query_advice(output, Rotation::cur());
-            vec![
-                s.clone() * (a_equals_b.expr() * (output.clone() - c)),
-                s * (Expression::Constant(F::one()) - a_equals_b.expr()) * (output - (a - b)),
-            ]
-        });
+        let select = SelectChip::configure(meta, is_zero_output_column, c, diff, output);
 
         FunctionConfig {
             selector,
             a,
             b,
             c,
+            diff,
             a_equals_b,
-            output,
+            select,
         }
     }
 
@@ -70,20 +81,32 @@ impl<F: FieldExt> FunctionChip<F> {
         b: F,
         c: F,
     ) -> Result<AssignedCell<F, F>, Error> {
-        let is_zero_chip = IsZeroChip::construct(self.config.a_equals_b.clone());
+        let is_zero_chip = IsZeroWithOutputChip::construct(self.config.a_equals_b.clone());
 
-        layouter.assign_region(
-            || "f(a, b, c) = if a == b {c} else {a - b}",
+        let (a_equals_b, c, diff) = layouter.assign_region(
+            || "a == b",
             |mut region| {
                 self.config.selector.enable(&mut region, 0)?;
                 region.assign_advice(|| "a", self.config.a, 0, || Value::known(a))?;
                 region.assign_advice(|| "b", self.config.b, 0, || Value::known(b))?;
-                region.assign_advice(|| "c", self.config.c, 0, || Value::known(c))?;
-                is_zero_chip.assign(&mut region, 0, Value::known(a - b))?;
-
-                let output = if a == b { c } else { a - b };
-                region.assign_advice(|| "output", self.config.output, 0, || Value::known(output))
+                let c = region.assign_advice(|| "c", self.config.c, 0, || Value::known(c))?;
+                let diff = region.assign_advice(
+                    || "a - b",
+                    self.config.diff,
+                    0,
+                    || Value::known(a - b),
+                )?;
+                let a_equals_b = is_zero_chip.assign(&mut region, 0, Value::known(a - b))?;
+
+                Ok((a_equals_b, c, diff))
             },
+        )?;
+
+        SelectChip::construct(self.config.select.clone()).select(
+            layouter.namespace(|| "f(a, b, c) = if a == b {c} else {a - b}"),
+            &a_equals_b,
+            &c,
+            &diff,
         )
     }
 }
@@ -129,5 +152,14 @@ mod tests {
 
         let prover = MockProver::run(4, &circuit, vec![]).unwrap();
         prover.assert_satisfied();
+
+        let circuit = FunctionCircuit {
+            a: Fp::from(10),
+            b: Fp::from(10),
+            c: Fp::from(15),
+        };
+
+        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
     }
 }
@@ -1,17 +1,31 @@
 use std::marker::PhantomData;
 use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
 
+use super::{FibonacciInstructions, Seeds};
+use crate::util::min_k;
+use crate::table_registry::TableRegistry;
+
+/// Width of the limbs an xor operand is split into; the xor table covers every pair of limbs.
+pub const LIMB_BITS: usize = 4;
+/// Number of limbs per xor operand, so operands are `LIMB_BITS * NUM_LIMBS = 32` bits wide.
+pub const NUM_LIMBS: usize = 8;
+
+const LIMB_MASK: u32 = (1 << LIMB_BITS) - 1;
+
 #[derive(Debug, Clone)]
-struct FibonacciConfig {
+pub struct FibonacciConfig {
     pub advice: [Column<Advice>; 3],
     pub s_add: Selector,
     pub s_xor: Selector,
+    pub s_limb: Selector,
     pub xor_table: [TableColumn; 3],
     pub instance: Column<Instance>,
+    /// The tables the chip loads itself; empty when they are shared through a registry.
+    tables: TableRegistry,
 }
 
 #[derive(Debug, Clone)]
-struct FibonacciChip<F: FieldExt> {
+pub struct FibonacciChip<F: FieldExt> {
     config: FibonacciConfig,
     _marker: PhantomData<F>,
 }
@@ -24,19 +38,28 @@ impl<F: FieldExt> FibonacciChip<F> {
         }
     }
 
+    /// The chip owns its xor table and loads it in `load`.
     pub fn configure(meta: &mut ConstraintSystem<F>) -> FibonacciConfig {
+        let mut tables = TableRegistry::new();
+        let config = Self::configure_with_tables(meta, &mut tables);
+        FibonacciConfig { tables, ..config }
+    }
+
+    /// Takes the xor table from `tables`, to share it with other chips. The caller loads it
+    /// through the registry, and `load` does nothing.
+    pub fn configure_with_tables(
+        meta: &mut ConstraintSystem<F>,
+        tables: &mut TableRegistry,
+    ) -> FibonacciConfig {
         let col_a = meta.advice_column();
         let col_b = meta.advice_column();
         let col_c = meta.advice_column();
         let s_add = meta.selector();
-        let s_xor = meta.complex_selector();
+        let s_xor = meta.selector();
+        let s_limb = meta.complex_selector();
         let instance = meta.instance_column();
 
-        let xor_table = [
-            meta.lookup_table_column(),
-            meta.lookup_table_column(),
-            meta.lookup_table_column(),
-        ];
+        let xor_table = tables.xor_table(meta, LIMB_BITS);
 
         meta.enable_equality(col_a);
         meta.enable_equality(col_b);
@@ -55,8 +78,35 @@ impl<F: FieldExt> FibonacciChip<F> {
             vec![s * (a + b - c)]
         });
 
-        meta.lookup(|meta| {
+        meta.create_gate("xor", |meta| {
+            //
+            // col_a | col_b | col_c | selector
+            //   a      b        c       s_xor
+            //   a_0    b_0      c_0     s_limb
+            //   ..     ..       ..      ..
+            //   a_7    b_7      c_7     s_limb
+            //
+            // Each operand is the little-endian recomposition of the limbs below it; the
+            // limbs themselves are checked against the xor table.
             let s = meta.query_selector(s_xor);
+            [col_a, col_b, col_c]
+                .iter()
+                .map(|column| {
+                    let value = meta.query_advice(*column, Rotation::cur());
+                    let recomposed = (0..NUM_LIMBS).rev().fold(
+                        Expression::Constant(F::zero()),
+                        |acc, i| {
+                            acc * Expression::Constant(F::from(1 << LIMB_BITS))
+                                + meta.query_advice(*column, Rotation(i as i32 + 1))
+                        },
+                    );
+                    s.clone() * (value - recomposed)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        meta.lookup(|meta| {
+            let s = meta.query_selector(s_limb);
             let lhs = meta.query_advice(col_a, Rotation::cur());
             let rhs = meta.query_advice(col_b, Rotation::cur());
             let out = meta.query_advice(col_c, Rotation::cur());
@@ -71,68 +121,103 @@ impl<F: FieldExt> FibonacciChip<F> {
             advice: [col_a, col_b, col_c],
             s_add,
             s_xor,
+            s_limb,
             xor_table,
             instance,
+            tables: TableRegistry::new(),
         }
     }
+}
 
-    fn load_table(
+impl<F: FieldExt> FibonacciChip<F> {
+    /// Assigns `a ^ b` at `offset` and the limbs of `a`, `b` and `a ^ b` on the rows below.
+    ///
+    /// Only the low 32 bits of the operands are used for the witness, so the recomposition
+    /// gate rejects any operand that does not fit in 32 bits.
+    fn assign_xor(
         &self,
-        mut layouter: impl Layouter<F>,
-    ) -> Result<(), Error> {
-        layouter.assign_table(
-            || "xor_table",
-            |mut table| {
-                let mut idx = 0;
-                for lhs in 0..32 {
-                    for rhs in 0..32 {
-                        table.assign_cell(
-                            || "lhs",
-                            self.config.xor_table[0],
-                            idx,
-                            || Value::known(F::from(lhs)),
-                        )?;
-                        table.assign_cell(
-                            || "rhs",
-                            self.config.xor_table[1],
-                            idx,
-                            || Value::known(F::from(rhs)),
-                        )?;
-                        table.assign_cell(
-                            || "lhs ^ rhs",
-                            self.config.xor_table[2],
-                            idx,
-                            || Value::known(F::from(lhs ^ rhs)),
-                        )?;
-                        idx += 1;
-                    }
-                }
-                Ok(())
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: Value<&F>,
+        b: Value<&F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.s_xor.enable(region, offset)?;
+
+        let a = a.map(|a| a.get_lower_32());
+        let b = b.map(|b| b.get_lower_32());
+        let c = a.zip(b).map(|(a, b)| a ^ b);
+
+        let c_cell = region.assign_advice(
+            || "a ^ b",
+            self.config.advice[2],
+            offset,
+            || c.map(|c| F::from(c as u64)),
+        )?;
+
+        for i in 0..NUM_LIMBS {
+            let row = offset + 1 + i;
+            self.config.s_limb.enable(region, row)?;
+
+            let limb = |x: u32| F::from(((x >> (i * LIMB_BITS)) & LIMB_MASK) as u64);
+            for (column, value) in self.config.advice.iter().zip([a, b, c]) {
+                region.assign_advice(|| "limb", *column, row, || value.map(limb))?;
             }
-        )
+        }
+
+        Ok(c_cell)
+    }
+}
+
+impl<F: FieldExt> Chip<F> for FibonacciChip<F> {
+    type Config = FibonacciConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
     }
 
-    #[allow(clippy::type_complexity)]
-    pub fn assign(
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> FibonacciInstructions<F> for FibonacciChip<F> {
+    type Num = AssignedCell<F, F>;
+
+    fn load(&self, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let mut tables = self.config.tables.loader();
+        tables.load_all(&mut layouter)?;
+        tables.finish()
+    }
+
+    fn assign(
         &self,
         mut layouter: impl Layouter<F>,
-        nrows: usize,
-    ) -> Result<AssignedCell<F, F>, Error> {
+        seeds: &Seeds<F>,
+        nterms: usize,
+    ) -> Result<Self::Num, Error> {
+        if nterms < 3 {
+            return Err(Error::Synthesis);
+        }
+
+        // The first row produces three terms, every further row produces one more.
+        let nrows = nterms - 2;
+
         layouter.assign_region(
             || "entire circuit",
             |mut region| {
                 self.config.s_add.enable(&mut region, 0)?;
 
                 // assign first row
-                let a_cell = region.assign_advice_from_instance(
-                    || "1",
+                let a_cell = seeds.assign(
+                    &mut region,
                     self.config.instance,
                     0,
                     self.config.advice[0],
                     0,
                 )?;
-                let mut b_cell = region.assign_advice_from_instance(
-                    || "1",
+                let mut b_cell = seeds.assign(
+                    &mut region,
                     self.config.instance,
                     1,
                     self.config.advice[1],
@@ -145,41 +230,42 @@ impl<F: FieldExt> FibonacciChip<F> {
                     || a_cell.value().copied() + b_cell.value(),
                 )?;
 
-                // assign the rest of rows
-                for row in 1..nrows {
+                // assign the rest of the steps; an add step takes one row, an xor step one
+                // row followed by its limbs
+                let mut offset = 1;
+                for step in 1..nrows {
                     b_cell.copy_advice(
                         || "a",
                         &mut region,
                         self.config.advice[0],
-                        row,
+                        offset,
                     )?;
                     c_cell.copy_advice(
                         || "b",
                         &mut region,
                         self.config.advice[1],
-                        row,
+                        offset,
                     )?;
 
-                    let new_c_cell = if row % 2 == 0 {
-                        self.config.s_add.enable(&mut region, row)?;
-                        region.assign_advice(
+                    let new_c_cell = if step % 2 == 0 {
+                        self.config.s_add.enable(&mut region, offset)?;
+                        let cell = region.assign_advice(
                             || "advice",
                             self.config.advice[2],
-                            row,
+                            offset,
                             || b_cell.value().copied() + c_cell.value(),
-                        )?
+                        )?;
+                        offset += 1;
+                        cell
                     } else {
-                        self.config.s_xor.enable(&mut region, row)?;
-                        region.assign_advice(
-                            || "advice",
-                            self.config.advice[2],
-                            row,
-                            || b_cell.value().and_then(|a| c_cell.value().map(|b| {
-                                let a_val = a.get_lower_32() as u64;
-                                let b_val = b.get_lower_32() as u64;
-                                F::from(a_val ^ b_val)
-                            })),
-                        )?
+                        let cell = self.assign_xor(
+                            &mut region,
+                            offset,
+                            b_cell.value(),
+                            c_cell.value(),
+                        )?;
+                        offset += 1 + NUM_LIMBS;
+                        cell
                     };
 
                     b_cell = c_cell;
@@ -191,25 +277,64 @@ impl<F: FieldExt> FibonacciChip<F> {
         )
     }
 
-    pub fn expose_public(
+    fn expose_public(
         &self,
         mut layouter: impl Layouter<F>,
-        cell: AssignedCell<F, F>,
+        num: &Self::Num,
         row: usize,
     ) -> Result<(), Error> {
-        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+        layouter.constrain_instance(num.cell(), self.config.instance, row)
     }
 }
 
+/// Proves the term at index `n` of the add/xor sequence seeded by f(0) and f(1), either
+/// public or private.
+///
+/// Both operands of every xor step must fit in 32 bits; the proof fails once the sequence
+/// outgrows them.
 #[derive(Default)]
-struct MyCircuit<F>(PhantomData<F>);
+pub struct MyCircuit<F> {
+    n: usize,
+    seeds: Seeds<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> MyCircuit<F> {
+    /// Seeds are read from instance rows 0 and 1.
+    pub fn new(n: usize) -> Self {
+        Self::with_seeds(n, Seeds::Public)
+    }
+
+    /// Seeds are private witnesses and only the output is public.
+    pub fn with_private_seeds(n: usize, f0: Value<F>, f1: Value<F>) -> Self {
+        Self::with_seeds(n, Seeds::Private(f0, f1))
+    }
+
+    fn with_seeds(n: usize, seeds: Seeds<F>) -> Self {
+        assert!(n >= 2, "the first row already holds f(0), f(1) and f(2)");
+        Self {
+            n,
+            seeds,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the smallest `k` that fits the circuit.
+    pub fn k(&self) -> u32 {
+        // The first row holds f(0), f(1) and f(2), each further step adds one term. Add
+        // steps take one row, xor steps (every other step) take one row per limb more. The
+        // xor table needs one row per pair of limbs.
+        let rows = self.n - 1 + NUM_LIMBS * ((self.n - 1) / 2);
+        min_k::<F, Self>(std::cmp::max(rows, 1 << (2 * LIMB_BITS)))
+    }
+}
 
 impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     type Config = FibonacciConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self::with_seeds(self.n, self.seeds.without_witnesses())
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -222,9 +347,9 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
         let chip = FibonacciChip::construct(config);
-        chip.load_table(layouter.namespace(|| "lookup table"))?;
-        let out_cell = chip.assign(layouter.namespace(|| "entire table"), 8)?;
-        chip.expose_public(layouter.namespace(|| "out"), out_cell, 2)?;
+        chip.load(layouter.namespace(|| "lookup table"))?;
+        let out_cell = chip.assign(layouter.namespace(|| "entire table"), &self.seeds, self.n + 1)?;
+        chip.expose_public(layouter.namespace(|| "out"), &out_cell, self.seeds.output_row())?;
 
         Ok(())
     }
@@ -233,18 +358,28 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
 #[cfg(test)]
 mod tests {
     use super::MyCircuit;
-    use std::marker::PhantomData;
-    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use halo2_proofs::{circuit::Value, dev::MockProver, pasta::Fp};
+
+    /// Computes the term at index `n`, alternating addition and xor as the circuit does.
+    fn add_xor(f0: u64, f1: u64, n: usize) -> u64 {
+        let (mut a, mut b) = (f0, f1);
+        for i in 0..n {
+            let c = if i % 2 == 0 { a + b } else { a ^ b };
+            a = b;
+            b = c;
+        }
+        a
+    }
 
     #[test]
     fn fibonacci_example4() {
-        let k = 11;
-
         let a = Fp::from(1); // F[0]
         let b = Fp::from(1); // F[1]
         let out = Fp::from(21); // F[9]
 
-        let circuit = MyCircuit(PhantomData);
+        let circuit = MyCircuit::new(9);
+        let k = circuit.k();
+        assert_eq!(k, 9);
 
         let mut public_input = vec![a, b, out];
 
@@ -257,6 +392,50 @@ mod tests {
         // _prover.assert_satisfied();
     }
 
+    #[test]
+    fn fibonacci_example4_any_n() {
+        // From n = 65 on, an xor step sees an operand wider than 32 bits.
+        for n in [2, 3, 10, 64] {
+            let circuit = MyCircuit::new(n);
+            let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(add_xor(1, 1, n))];
+
+            let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn fibonacci_example4_rejects_wide_operands() {
+        // f(2) = 2^32 does not fit the xor step that follows it.
+        let f0 = u32::MAX as u64;
+        let circuit = MyCircuit::new(3);
+        let public_input = vec![Fp::from(f0), Fp::from(1), Fp::from(add_xor(f0, 1, 3))];
+
+        let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let n = 65;
+        let circuit = MyCircuit::new(n);
+        let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(add_xor(1, 1, n))];
+
+        let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn fibonacci_example4_private_seeds() {
+        let (a, b) = (Fp::from(1), Fp::from(2));
+        let out = Fp::from(add_xor(1, 2, 9));
+
+        let circuit = MyCircuit::with_private_seeds(9, Value::known(a), Value::known(b));
+        let prover = MockProver::run(circuit.k(), &circuit, vec![vec![out]]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = MyCircuit::with_private_seeds(9, Value::known(b), Value::known(a));
+        let prover = MockProver::run(circuit.k(), &circuit, vec![vec![out]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn plot_fibonacci1() {
@@ -266,9 +445,9 @@ mod tests {
         root.fill(&WHITE).unwrap();
         let root = root.titled("Fib 1 Layout", ("sans-serif", 60)).unwrap();
 
-        let circuit = MyCircuit::<Fp>(PhantomData);
+        let circuit = MyCircuit::<Fp>::new(9);
         halo2_proofs::dev::CircuitLayout::default()
-            .render(4, &circuit, &root)
+            .render(circuit.k(), &circuit, &root)
             .unwrap();
     }
 }
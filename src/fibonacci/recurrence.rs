@@ -0,0 +1,300 @@
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+/// How the terms of the sequence are laid out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// One row per step over `order + 1` advice columns, as in `example1`; each row copies
+    /// the last `order` terms of the row above.
+    Row,
+    /// The whole sequence in a single advice column, as in `example2`; the gate reaches the
+    /// next `order` terms through rotations.
+    Column,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecurrenceConfig {
+    order: usize,
+    layout: Layout,
+    advice: Vec<Column<Advice>>,
+    coeffs: Vec<Column<Fixed>>,
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+/// Proves terms of `x_{i+k} = sum_j c_j * x_{i+j}`, for `j` in `0..k`.
+///
+/// The order `k` is fixed when the chip is configured; the coefficients `c_j` are assigned
+/// to fixed columns, so the same configuration covers every recurrence of that order.
+#[derive(Debug, Clone)]
+pub struct RecurrenceChip<F: FieldExt> {
+    config: RecurrenceConfig,
+    coeffs: Vec<F>,
+}
+
+impl<F: FieldExt> RecurrenceChip<F> {
+    pub fn construct(config: RecurrenceConfig, coeffs: Vec<F>) -> Self {
+        assert_eq!(coeffs.len(), config.order, "one coefficient per previous term");
+        Self { config, coeffs }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        order: usize,
+        layout: Layout,
+    ) -> RecurrenceConfig {
+        assert!(order >= 1);
+
+        let advice: Vec<_> = match layout {
+            Layout::Row => (0..=order).map(|_| meta.advice_column()).collect(),
+            Layout::Column => vec![meta.advice_column()],
+        };
+        let coeffs: Vec<_> = (0..order).map(|_| meta.fixed_column()).collect();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+
+        for column in advice.iter() {
+            meta.enable_equality(*column);
+        }
+        meta.enable_equality(instance);
+
+        meta.create_gate("linear recurrence", |meta| {
+            //
+            // Layout::Row                        | Layout::Column
+            //
+            // x_i | .. | x_{i+k} | c_0 .. | s    | advice  | c_0 .. | s
+            //                                    | x_i     |  c_j   | s
+            //                                    | ..      |        |
+            //                                    | x_{i+k} |        |
+            //
+            let s = meta.query_selector(selector);
+            let terms: Vec<_> = match layout {
+                Layout::Row => advice
+                    .iter()
+                    .map(|column| meta.query_advice(*column, Rotation::cur()))
+                    .collect(),
+                Layout::Column => (0..=order)
+                    .map(|j| meta.query_advice(advice[0], Rotation(j as i32)))
+                    .collect(),
+            };
+
+            let sum = coeffs.iter().zip(terms.iter()).fold(
+                Expression::Constant(F::zero()),
+                |acc, (c, x)| acc + meta.query_fixed(*c, Rotation::cur()) * x.clone(),
+            );
+            vec![s * (sum - terms[order].clone())]
+        });
+
+        RecurrenceConfig {
+            order,
+            layout,
+            advice,
+            coeffs,
+            selector,
+            instance,
+        }
+    }
+
+    /// Assigns the first `nterms` terms, reading the `order` seeds from instance rows
+    /// `0..order`, and returns the last term.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        nterms: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let order = self.config.order;
+        assert!(nterms > order, "the gate needs at least one term after the seeds");
+
+        layouter.assign_region(
+            || "entire recurrence table",
+            |mut region| {
+                // Each enabled step checks one term after the seeds.
+                for step in 0..nterms - order {
+                    self.config.selector.enable(&mut region, step)?;
+                    for (column, c) in self.config.coeffs.iter().zip(self.coeffs.iter()) {
+                        region.assign_fixed(|| "c", *column, step, || Value::known(*c))?;
+                    }
+                }
+
+                // The last `order` terms, oldest first.
+                let mut window = (0..order)
+                    .map(|j| {
+                        let (column, offset) = self.position(0, j);
+                        region.assign_advice_from_instance(
+                            || format!("x({})", j),
+                            self.config.instance,
+                            j,
+                            column,
+                            offset,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                for step in 0..nterms - order {
+                    if self.config.layout == Layout::Row && step > 0 {
+                        // Carry the window over from the row above.
+                        window = window
+                            .iter()
+                            .enumerate()
+                            .map(|(j, x)| {
+                                x.copy_advice(|| "x", &mut region, self.config.advice[j], step)
+                            })
+                            .collect::<Result<Vec<_>, _>>()?;
+                    }
+
+                    let next = window.iter().zip(self.coeffs.iter()).fold(
+                        Value::known(F::zero()),
+                        |acc, (x, c)| acc + x.value().map(|x| *x * c),
+                    );
+                    let (column, offset) = self.position(step, order);
+                    let cell = region.assign_advice(
+                        || format!("x({})", step + order),
+                        column,
+                        offset,
+                        || next,
+                    )?;
+
+                    window.remove(0);
+                    window.push(cell);
+                }
+
+                Ok(window.pop().unwrap())
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(num.cell(), self.config.instance, row)
+    }
+
+    /// Returns where term `j` of the window checked at `step` lives.
+    fn position(&self, step: usize, j: usize) -> (Column<Advice>, usize) {
+        match self.config.layout {
+            Layout::Row => (self.config.advice[j], step),
+            Layout::Column => (self.config.advice[0], step + j),
+        }
+    }
+}
+
+impl<F: FieldExt> Chip<F> for RecurrenceChip<F> {
+    type Config = RecurrenceConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Layout, RecurrenceChip, RecurrenceConfig};
+    use crate::fibonacci::fibonacci;
+    use crate::util::min_k;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::*,
+        dev::MockProver,
+        pasta::Fp,
+        plonk::*,
+    };
+
+    /// Proves x(n) of a recurrence of order `ORDER`; `COLUMN` selects `Layout::Column`.
+    struct TestCircuit<F, const ORDER: usize, const COLUMN: bool> {
+        coeffs: Vec<F>,
+        n: usize,
+    }
+
+    impl<F: FieldExt, const ORDER: usize, const COLUMN: bool> TestCircuit<F, ORDER, COLUMN> {
+        fn k(&self) -> u32 {
+            let rows = if COLUMN { self.n + 1 } else { self.n + 1 - ORDER };
+            min_k::<F, Self>(rows)
+        }
+    }
+
+    impl<F: FieldExt, const ORDER: usize, const COLUMN: bool> Circuit<F>
+        for TestCircuit<F, ORDER, COLUMN>
+    {
+        type Config = RecurrenceConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                coeffs: self.coeffs.clone(),
+                n: self.n,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let layout = if COLUMN { Layout::Column } else { Layout::Row };
+            RecurrenceChip::configure(meta, ORDER, layout)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = RecurrenceChip::construct(config, self.coeffs.clone());
+
+            let out = chip.assign(layouter.namespace(|| "entire table"), self.n + 1)?;
+            chip.expose_public(layouter.namespace(|| "out"), &out, ORDER)
+        }
+    }
+
+    /// Runs both layouts on `seeds`, expecting x(n) to be `out`, and checks that a wrong
+    /// output is rejected.
+    fn check<const ORDER: usize>(coeffs: [u64; ORDER], seeds: [u64; ORDER], n: usize, out: Fp) {
+        let coeffs: Vec<_> = coeffs.iter().map(|c| Fp::from(*c)).collect();
+        let mut public_input: Vec<_> = seeds.iter().map(|x| Fp::from(*x)).collect();
+        public_input.push(out);
+
+        let row = TestCircuit::<Fp, ORDER, false> {
+            coeffs: coeffs.clone(),
+            n,
+        };
+        let prover = MockProver::run(row.k(), &row, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        let column = TestCircuit::<Fp, ORDER, true> { coeffs, n };
+        let prover = MockProver::run(column.k(), &column, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        public_input[ORDER] += Fp::one();
+        let prover = MockProver::run(row.k(), &row, vec![public_input.clone()]).unwrap();
+        assert!(prover.verify().is_err());
+        let prover = MockProver::run(column.k(), &column, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn fibonacci_recurrence() {
+        check([1, 1], [1, 1], 9, Fp::from(55));
+        check([1, 1], [1, 1], 50, fibonacci(Fp::one(), Fp::one(), 50));
+    }
+
+    #[test]
+    fn lucas_recurrence() {
+        // 2, 1, 3, 4, 7, 11, 18, 29, 47, 76, 123
+        check([1, 1], [2, 1], 10, Fp::from(123));
+    }
+
+    #[test]
+    fn pell_recurrence() {
+        // x_{i+2} = x_i + 2 x_{i+1}: 0, 1, 2, 5, 12, 29, 70, 169, 408, 985, 2378
+        check([1, 2], [0, 1], 10, Fp::from(2378));
+    }
+
+    #[test]
+    fn tribonacci_recurrence() {
+        // 0, 0, 1, 1, 2, 4, 7, 13, 24, 44, 81
+        check([1, 1, 1], [0, 0, 1], 10, Fp::from(81));
+    }
+}
@@ -0,0 +1,382 @@
+use std::marker::PhantomData;
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+use crate::util::min_k;
+use crate::is_zero::{IsZeroChip, IsZeroConfig};
+
+/// Index of the instance row holding `n`; rows 0 and 1 hold f(0) and f(1).
+pub const N_ROW: usize = 2;
+/// Index of the instance row holding F(n).
+pub const OUTPUT_ROW: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct VariableLengthConfig<F: FieldExt> {
+    f: Column<Advice>,
+    n: Column<Advice>,
+    done: Column<Advice>,
+    out: Column<Advice>,
+    idx: Column<Fixed>,
+    q_fib: Selector,
+    q_first: Selector,
+    q_step: Selector,
+    at_n: IsZeroConfig<F>,
+    instance: Column<Instance>,
+}
+
+/// Proves F(n) for a public `n` in `0..=MAX`, always laying out all `MAX + 1` terms so that
+/// every `n` shares the same verifying key.
+///
+/// Row `i` holds f(i) next to its index `i`, and `IsZeroChip` flags the row where `i == n`.
+/// The running `done` flag counts flagged rows and `out` accumulates the flagged term, so the
+/// last row holds F(n); `done` must end at 1, which rules out any `n` above `MAX`.
+///
+///     f    | idx |  n  | done | out  | q_fib | q_first | q_step
+///     f(0) |  0  |  n  | d_0  | o_0  |   1   |    1    |   0
+///     f(1) |  1  |  n  | d_1  | o_1  |   1   |    0    |   1
+///     ...  | ... | ... | ...  | ...  |  ...  |   ...   |  ...
+///     f(M) |  M  |  n  |  1   | F(n) |   0   |    0    |   1
+#[derive(Debug, Clone)]
+pub struct VariableLengthChip<F: FieldExt, const MAX: usize> {
+    config: VariableLengthConfig<F>,
+}
+
+impl<F: FieldExt, const MAX: usize> VariableLengthChip<F, MAX> {
+    pub fn construct(config: VariableLengthConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> VariableLengthConfig<F> {
+        let f = meta.advice_column();
+        let n = meta.advice_column();
+        let done = meta.advice_column();
+        let out = meta.advice_column();
+        let idx = meta.fixed_column();
+        let constants = meta.fixed_column();
+        let q_fib = meta.selector();
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(f);
+        meta.enable_equality(n);
+        meta.enable_equality(done);
+        meta.enable_equality(out);
+        meta.enable_equality(instance);
+        meta.enable_constant(constants);
+
+        let value_inv = meta.advice_column();
+        let at_n = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_first) + meta.query_selector(q_step),
+            |meta| meta.query_fixed(idx, Rotation::cur()) - meta.query_advice(n, Rotation::cur()),
+            value_inv,
+        );
+
+        meta.create_gate("add", |meta| {
+            let s = meta.query_selector(q_fib);
+            let a = meta.query_advice(f, Rotation::cur());
+            let b = meta.query_advice(f, Rotation::next());
+            let c = meta.query_advice(f, Rotation(2));
+            vec![s * (a + b - c)]
+        });
+
+        meta.create_gate("first row", |meta| {
+            let s = meta.query_selector(q_first);
+            let f = meta.query_advice(f, Rotation::cur());
+            let done = meta.query_advice(done, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            Constraints::with_selector(
+                s,
+                [
+                    ("done", done - at_n.expr()),
+                    ("out", out - at_n.expr() * f),
+                ],
+            )
+        });
+
+        meta.create_gate("next row", |meta| {
+            let s = meta.query_selector(q_step);
+            let f = meta.query_advice(f, Rotation::cur());
+            let n_prev = meta.query_advice(n, Rotation::prev());
+            let n = meta.query_advice(n, Rotation::cur());
+            let done_prev = meta.query_advice(done, Rotation::prev());
+            let done = meta.query_advice(done, Rotation::cur());
+            let out_prev = meta.query_advice(out, Rotation::prev());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            Constraints::with_selector(
+                s,
+                [
+                    ("n", n - n_prev),
+                    ("done", done - (done_prev + at_n.expr())),
+                    ("out", out - (out_prev + at_n.expr() * f)),
+                ],
+            )
+        });
+
+        VariableLengthConfig {
+            f,
+            n,
+            done,
+            out,
+            idx,
+            q_fib,
+            q_first,
+            q_step,
+            at_n,
+            instance,
+        }
+    }
+
+    /// Assigns f(0) to f(MAX), reading f(0), f(1) and `n` from the instance, and returns
+    /// the cell holding F(n).
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        n: Value<u64>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let at_n = IsZeroChip::construct(self.config.at_n.clone());
+
+        layouter.assign_region(
+            || "variable length fibonacci",
+            |mut region| {
+                let mut f: Vec<AssignedCell<F, F>> = vec![];
+                let mut done: Option<AssignedCell<F, F>> = None;
+                let mut out: Option<AssignedCell<F, F>> = None;
+
+                for row in 0..=MAX {
+                    if row + 2 <= MAX {
+                        self.config.q_fib.enable(&mut region, row)?;
+                    }
+                    if row == 0 {
+                        self.config.q_first.enable(&mut region, row)?;
+                    } else {
+                        self.config.q_step.enable(&mut region, row)?;
+                    }
+
+                    let f_cell = if row < 2 {
+                        region.assign_advice_from_instance(
+                            || format!("f({})", row),
+                            self.config.instance,
+                            row,
+                            self.config.f,
+                            row,
+                        )?
+                    } else {
+                        region.assign_advice(
+                            || format!("f({})", row),
+                            self.config.f,
+                            row,
+                            || f[row - 2].value().copied() + f[row - 1].value(),
+                        )?
+                    };
+
+                    region.assign_fixed(
+                        || "idx",
+                        self.config.idx,
+                        row,
+                        || Value::known(F::from(row as u64)),
+                    )?;
+                    if row == 0 {
+                        region.assign_advice_from_instance(
+                            || "n",
+                            self.config.instance,
+                            N_ROW,
+                            self.config.n,
+                            row,
+                        )?;
+                    } else {
+                        region.assign_advice(
+                            || "n",
+                            self.config.n,
+                            row,
+                            || n.map(F::from),
+                        )?;
+                    }
+
+                    let diff = n.map(|n| F::from(row as u64) - F::from(n));
+                    at_n.assign(&mut region, row, diff)?;
+
+                    // 1 on the row where idx == n, else 0.
+                    let flag = n.map(|n| if n == row as u64 { F::one() } else { F::zero() });
+                    let next_done = match &done {
+                        Some(done) => done.value().copied() + flag,
+                        None => flag,
+                    };
+                    let next_out = match &out {
+                        Some(out) => out.value().copied() + f_cell.value().copied() * flag,
+                        None => f_cell.value().copied() * flag,
+                    };
+
+                    done = Some(region.assign_advice(
+                        || "done",
+                        self.config.done,
+                        row,
+                        || next_done,
+                    )?);
+                    out = Some(region.assign_advice(|| "out", self.config.out, row, || next_out)?);
+                    f.push(f_cell);
+                }
+
+                // Exactly one row matched `n`.
+                region.constrain_constant(done.unwrap().cell(), F::one())?;
+
+                Ok(out.unwrap())
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const MAX: usize> Chip<F> for VariableLengthChip<F, MAX> {
+    type Config = VariableLengthConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+/// Proves F(n) for the sequence seeded by the public inputs f(0) and f(1), for any public
+/// `n` up to `MAX`.
+#[derive(Default)]
+pub struct MyCircuit<F, const MAX: usize> {
+    n: Value<u64>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const MAX: usize> MyCircuit<F, MAX> {
+    pub fn new(n: u64) -> Self {
+        Self {
+            n: Value::known(n),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the smallest `k` that fits the circuit.
+    pub fn k() -> u32 {
+        // One row per term, f(0) through f(MAX).
+        min_k::<F, Self>(MAX + 1)
+    }
+}
+
+impl<F: FieldExt, const MAX: usize> Circuit<F> for MyCircuit<F, MAX> {
+    type Config = VariableLengthConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        VariableLengthChip::<F, MAX>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = VariableLengthChip::<F, MAX>::construct(config);
+
+        let out = chip.assign(layouter.namespace(|| "entire table"), self.n)?;
+        chip.expose_public(layouter.namespace(|| "out"), &out, OUTPUT_ROW)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MyCircuit;
+    use crate::fibonacci::fibonacci;
+    use halo2_proofs::{
+        dev::MockProver,
+        pasta::{EqAffine, Fp},
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, SingleVerifier},
+        poly::commitment::Params,
+        transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+    };
+    use rand_core::OsRng;
+
+    const MAX: usize = 20;
+    type VariableLengthCircuit = MyCircuit<Fp, MAX>;
+
+    fn public_input(n: u64, out: Fp) -> Vec<Fp> {
+        vec![Fp::one(), Fp::one(), Fp::from(n), out]
+    }
+
+    #[test]
+    fn fibonacci_variable_length() {
+        let k = VariableLengthCircuit::k();
+
+        for n in 0..=MAX as u64 {
+            let out = fibonacci(Fp::one(), Fp::one(), n as usize);
+            let circuit = VariableLengthCircuit::new(n);
+
+            let prover = MockProver::run(k, &circuit, vec![public_input(n, out)]).unwrap();
+            prover.assert_satisfied();
+
+            let prover =
+                MockProver::run(k, &circuit, vec![public_input(n, out + Fp::one())]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        // No row matches an `n` past the maximum.
+        let n = MAX as u64 + 1;
+        let out = fibonacci(Fp::one(), Fp::one(), n as usize);
+        let prover =
+            MockProver::run(k, &VariableLengthCircuit::new(n), vec![public_input(n, out)]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn fibonacci_variable_length_one_vk() {
+        let k = VariableLengthCircuit::k();
+        let params: Params<EqAffine> = Params::new(k);
+
+        // Keys are generated once, without knowing n.
+        let empty = VariableLengthCircuit::default();
+        let vk = keygen_vk(&params, &empty).unwrap();
+        let pk = keygen_pk(&params, vk, &empty).unwrap();
+
+        for n in [3, 17] {
+            let public_input = public_input(n, fibonacci(Fp::one(), Fp::one(), n as usize));
+
+            let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(vec![]);
+            create_proof(
+                &params,
+                &pk,
+                &[VariableLengthCircuit::new(n)],
+                &[&[&public_input]],
+                &mut OsRng,
+                &mut transcript,
+            )
+            .unwrap();
+            let proof = transcript.finalize();
+
+            let mut transcript = Blake2bRead::<_, EqAffine, Challenge255<_>>::init(&proof[..]);
+            verify_proof(
+                &params,
+                pk.get_vk(),
+                SingleVerifier::new(&params),
+                &[&[&public_input]],
+                &mut transcript,
+            )
+            .unwrap();
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Public entry point for the chips in this crate.
+//!
+//! The example modules keep their tutorial layout; this module re-exports the reusable
+//! configs and chips under stable paths so they can be used from other crates.
+
+/// AND, OR, XOR and NOT of small values through one tagged lookup table.
+pub mod bitwise {
+    pub use crate::bitwise::{BitwiseChip, BitwiseConfig, BitwiseOp, BitwiseTableConfig};
+}
+
+/// Boolean-constrained cells and the gates combining them.
+pub mod boolean {
+    pub use crate::boolean::{AssignedBit, BoolChip, BoolConfig};
+}
+
+/// Checks whether two cells hold the same value, returning the result as a cell.
+pub mod is_equal {
+    pub use crate::is_equal::{IsEqualChip, IsEqualConfig};
+}
+
+/// Checks whether an expression evaluates to zero.
+pub mod is_zero {
+    pub use crate::is_zero::{
+        IsZeroBatchChip, IsZeroBatchConfig, IsZeroChip, IsZeroConfig, IsZeroWithOutputChip,
+        IsZeroWithOutputConfig,
+    };
+}
+
+/// Fibonacci chips in different layouts.
+pub mod fibonacci {
+    pub use crate::fibonacci::{FibonacciInstructions, Seeds, OUTPUT_ROW};
+
+    /// One row per step over three advice columns, `a + b = c`.
+    pub mod three_column {
+        pub use crate::fibonacci::example1::{
+            FibonacciChip, FibonacciConfig, MyCircuit as FibonacciCircuit,
+        };
+    }
+
+    /// The whole sequence in a single advice column, using rotations.
+    pub mod single_column {
+        pub use crate::fibonacci::example2::{
+            FibonacciChip, FibonacciConfig, MyCircuit as FibonacciCircuit,
+        };
+    }
+
+    /// `f(a, b, c) = if a == b { c } else { a - b }`, built on `IsZeroChip` and `SelectChip`.
+    pub mod function {
+        pub use crate::fibonacci::example3::{FunctionChip, FunctionConfig};
+    }
+
+    /// Alternating addition and 32-bit XOR steps, with XOR checked limb by limb in a
+    /// lookup table.
+    pub mod add_xor {
+        pub use crate::fibonacci::example4::{
+            FibonacciChip, FibonacciConfig, MyCircuit as FibonacciCircuit, LIMB_BITS, NUM_LIMBS,
+        };
+    }
+
+    /// F(n) in `log n` rows, from the doubling identities and the bits of a public `n`.
+    pub mod doubling {
+        pub use crate::fibonacci::doubling::{
+            DoublingChip, DoublingConfig, MyCircuit as DoublingCircuit, N_ROW, OUTPUT_ROW,
+        };
+    }
+
+    /// F(n) for any public `n` up to a fixed maximum, under a single verifying key.
+    pub mod variable_length {
+        pub use crate::fibonacci::variable_length::{
+            MyCircuit as VariableLengthCircuit, VariableLengthChip, VariableLengthConfig, N_ROW,
+            OUTPUT_ROW,
+        };
+    }
+
+    /// `x_{i+k} = sum c_j * x_{i+j}` for any order `k`, in either the row or column layout.
+    pub mod recurrence {
+        pub use crate::fibonacci::recurrence::{Layout, RecurrenceChip, RecurrenceConfig};
+    }
+}
+
+/// `a < b`, `a <= b`, `a > b` and `a >= b` for values in `0..RANGE`, as boolean cells.
+pub mod less_than {
+    pub use crate::less_than::{LessThanChip, LessThanConfig};
+}
+
+/// `min`, `max` and `clamp` of values in `0..RANGE`, with the ordering proven in-circuit.
+pub mod min_max {
+    pub use crate::min_max::{MinMaxChip, MinMaxConfig};
+}
+
+/// Piecewise functions of several inputs, described with a builder and compiled to a gate.
+pub mod piecewise {
+    pub use crate::piecewise::{
+        Condition, Expr, PiecewiseBuilder, PiecewiseChip, PiecewiseConfig, PiecewiseFunction,
+    };
+}
+
+/// Range checks on witnessed values.
+pub mod range_check {
+    pub use crate::range_check::chip::{
+        RangeCheckChip, RangeCheckConfig, RangeConstrained, Strategy,
+    };
+
+    /// `0 <= v < RANGE` using a polynomial expression.
+    pub mod expression {
+        pub use crate::range_check::example1::{RangeCheckConfig, RangeConstrained};
+    }
+
+    /// Values of up to 128 bits, decomposed into windows looked up in a range table.
+    pub mod decompose {
+        pub use crate::range_check::decompose::{DecomposeChip, DecomposeConfig};
+    }
+
+    /// `lo <= v < hi` for arbitrary bounds, given as constants or cells.
+    pub mod interval {
+        pub use crate::range_check::interval::{Bound, IntervalChip, IntervalConfig};
+    }
+
+    /// A polynomial expression for small ranges and a lookup table for large ones.
+    pub mod lookup {
+        pub use crate::range_check::example2::{
+            RangeCheckConfig, RangeConstrained, RangeTableConfig,
+        };
+    }
+
+    /// `v < limit` for a limit taken from the instance column, under a single verifying key.
+    pub mod public_bound {
+        pub use crate::range_check::public_bound::{
+            MyCircuit as PublicBoundCircuit, PublicBoundChip, PublicBoundConfig, LIMIT_ROW,
+        };
+    }
+
+    /// `v` has exactly `num_bits` bits, checked against a table tagged by bit length.
+    pub mod tagged {
+        pub use crate::range_check::example3::{
+            RangeCheckConfig, RangeConstrained, RangeTableConfig,
+        };
+    }
+
+    /// Like `tagged`, with the bit length computed from the value and returned as a cell.
+    pub mod bit_length {
+        pub use crate::range_check::example6::{
+            RangeCheckConfig, RangeCheckTable, RangeConstrained,
+        };
+    }
+}
+
+/// `cond ? x : y` for a boolean cell `cond`.
+pub mod select {
+    pub use crate::select::{SelectChip, SelectConfig};
+}
+
+/// Lookup tables shared between chips, allocated and loaded once per `(kind, size)`.
+pub mod table_registry {
+    pub use crate::table_registry::{TableKind, TableLoader, TableRegistry};
+}
+
+/// Helpers for sizing circuits.
+pub mod util {
+    pub use crate::util::min_k;
+}
+
+/// Whether all, or at least one, of a list of cells are zero, in a constant number of
+/// columns.
+pub mod zero_aggregate {
+    pub use crate::zero_aggregate::{ZeroAggregateChip, ZeroAggregateConfig};
+}
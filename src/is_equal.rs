@@ -0,0 +1,201 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use crate::is_zero::{IsZeroWithOutputChip, IsZeroWithOutputConfig};
+
+/// This chip assigns `1` if two cells hold the same value and `0` otherwise, by checking
+/// `lhs - rhs` with `IsZeroWithOutputChip`.
+///
+///       lhs  |  rhs  |  value_inv  |  output  |  selector
+///     ------------------------------------------------------
+///        a   |   b   | 1 / (a - b) |  a == b  |     1
+///
+#[derive(Debug, Clone)]
+pub struct IsEqualConfig<F: FieldExt> {
+    lhs: Column<Advice>,
+    rhs: Column<Advice>,
+    selector: Selector,
+    pub is_zero: IsZeroWithOutputConfig<F>,
+}
+
+pub struct IsEqualChip<F: FieldExt> {
+    config: IsEqualConfig<F>,
+}
+
+impl<F: FieldExt> Chip<F> for IsEqualChip<F> {
+    type Config = IsEqualConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> IsEqualChip<F> {
+    pub fn construct(config: IsEqualConfig<F>) -> Self {
+        IsEqualChip { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        lhs: Column<Advice>,
+        rhs: Column<Advice>,
+        value_inv: Column<Advice>,
+        output: Column<Advice>,
+    ) -> IsEqualConfig<F> {
+        let selector = meta.selector();
+
+        meta.enable_equality(lhs);
+        meta.enable_equality(rhs);
+
+        let is_zero = IsZeroWithOutputChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| {
+                meta.query_advice(lhs, Rotation::cur()) - meta.query_advice(rhs, Rotation::cur())
+            },
+            value_inv,
+            output,
+        );
+
+        IsEqualConfig {
+            lhs,
+            rhs,
+            selector,
+            is_zero,
+        }
+    }
+
+    /// Returns a cell holding `1` if `lhs == rhs` and `0` otherwise.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let is_zero = IsZeroWithOutputChip::construct(self.config.is_zero.clone());
+
+        layouter.assign_region(
+            || "lhs == rhs",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                lhs.copy_advice(|| "lhs", &mut region, self.config.lhs, 0)?;
+                rhs.copy_advice(|| "rhs", &mut region, self.config.rhs, 0)?;
+
+                let diff = lhs.value().copied() - rhs.value();
+                is_zero.assign(&mut region, 0, diff)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    #[derive(Debug, Clone)]
+    struct TestConfig<F: FieldExt> {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        is_equal: IsEqualConfig<F>,
+    }
+
+    /// Compares each pair and exposes the results in order.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        pairs: Vec<(F, F)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = TestConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                pairs: self.pairs.clone(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+
+            let [lhs, rhs, value_inv, output] = [(); 4].map(|_| meta.advice_column());
+            let is_equal = IsEqualChip::configure(meta, lhs, rhs, value_inv, output);
+
+            TestConfig {
+                input,
+                instance,
+                is_equal,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = IsEqualChip::construct(config.is_equal);
+
+            for (row, (lhs, rhs)) in self.pairs.iter().enumerate() {
+                let (lhs, rhs) = layouter.assign_region(
+                    || "operands",
+                    |mut region| {
+                        let lhs =
+                            region.assign_advice(|| "lhs", config.input, 0, || Value::known(*lhs))?;
+                        let rhs =
+                            region.assign_advice(|| "rhs", config.input, 1, || Value::known(*rhs))?;
+                        Ok((lhs, rhs))
+                    },
+                )?;
+
+                let out = chip.assign(layouter.namespace(|| "is equal"), &lhs, &rhs)?;
+                layouter.constrain_instance(out.cell(), config.instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_is_equal_chip() {
+        let k = 5;
+        let circuit = MyCircuit {
+            pairs: vec![
+                (Fp::from(3), Fp::from(3)),
+                (Fp::from(3), Fp::from(4)),
+                (Fp::zero(), Fp::zero()),
+                (-Fp::one(), Fp::one()),
+            ],
+        };
+
+        let expected = vec![Fp::one(), Fp::zero(), Fp::one(), Fp::zero()];
+        let prover = MockProver::run(k, &circuit, vec![expected]).unwrap();
+        prover.assert_satisfied();
+
+        // Claiming the opposite result for the first pair fails.
+        let flipped = vec![Fp::zero(), Fp::zero(), Fp::one(), Fp::zero()];
+        let prover = MockProver::run(k, &circuit, vec![flipped]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|f| matches!(f, VerifyFailure::Permutation { .. })));
+    }
+}
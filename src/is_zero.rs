@@ -3,6 +3,8 @@ use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
 #[derive(Clone, Debug)]
 pub struct IsZeroConfig<F> {
     pub value_inv: Column<Advice>,
+    /// Row of `value_inv`, relative to the row the gate is enabled on.
+    pub rotation: Rotation,
     pub is_zero_expr: Expression<F>,
 }
 
@@ -26,6 +28,18 @@ impl<F: FieldExt> IsZeroChip<F> {
         q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
         value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
         value_inv: Column<Advice>,
+    ) -> IsZeroConfig<F> {
+        Self::configure_with_rotation(meta, q_enable, value, value_inv, Rotation::cur())
+    }
+
+    /// Like `configure`, but reads `value_inv` at `rotation` from the row the gate is enabled
+    /// on. Checks configured with different rotations can share one `value_inv` column.
+    pub fn configure_with_rotation(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value_inv: Column<Advice>,
+        rotation: Rotation,
     ) -> IsZeroConfig<F> {
         let mut is_zero_expr = Expression::Constant(F::zero());
 
@@ -40,7 +54,7 @@ impl<F: FieldExt> IsZeroChip<F> {
             //
             let value = value(meta);
             let q_enable = q_enable(meta);
-            let value_inv = meta.query_advice(value_inv, Rotation::cur());
+            let value_inv = meta.query_advice(value_inv, rotation);
 
             is_zero_expr = Expression::Constant(F::one()) - value.clone() * value_inv;
             vec![q_enable * value * is_zero_expr.clone()]
@@ -48,10 +62,13 @@ impl<F: FieldExt> IsZeroChip<F> {
 
         IsZeroConfig {
             value_inv,
+            rotation,
             is_zero_expr,
         }
     }
 
+    /// Assigns the inverse witness for `value`, where `offset` is the row the gate is
+    /// enabled on. Fails with `Error::Synthesis` if the rotation points above the region.
     pub fn assign(
         &self,
         region: &mut Region<'_, F>,
@@ -59,7 +76,143 @@ impl<F: FieldExt> IsZeroChip<F> {
         value: Value<F>,
     ) -> Result<(), Error> {
         let value_inv = value.map(|value| value.invert().unwrap_or(F::zero()));
+        let offset = offset
+            .checked_add_signed(self.config.rotation.0 as isize)
+            .ok_or(Error::Synthesis)?;
         region.assign_advice(|| "value inv", self.config.value_inv, offset, || value_inv)?;
         Ok(())
     }
 }
+
+/// An `IsZeroConfig` whose result is also assigned to an advice cell.
+#[derive(Clone, Debug)]
+pub struct IsZeroWithOutputConfig<F> {
+    pub is_zero: IsZeroConfig<F>,
+    pub output: Column<Advice>,
+}
+
+impl<F: FieldExt> IsZeroWithOutputConfig<F> {
+    pub fn expr(&self) -> Expression<F> {
+        self.is_zero.expr()
+    }
+}
+
+/// Like `IsZeroChip`, but `assign` returns a cell holding `1` if the value is zero and `0`
+/// otherwise, which other regions can copy-constrain to.
+pub struct IsZeroWithOutputChip<F: FieldExt> {
+    config: IsZeroWithOutputConfig<F>,
+}
+
+impl<F: FieldExt> IsZeroWithOutputChip<F> {
+    pub fn construct(config: IsZeroWithOutputConfig<F>) -> Self {
+        IsZeroWithOutputChip { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value_inv: Column<Advice>,
+        output: Column<Advice>,
+    ) -> IsZeroWithOutputConfig<F> {
+        let is_zero = IsZeroChip::configure(meta, &q_enable, value, value_inv);
+
+        meta.enable_equality(output);
+
+        meta.create_gate("is_zero output", |meta| {
+            let q_enable = q_enable(meta);
+            let output = meta.query_advice(output, Rotation::cur());
+            vec![q_enable * (output - is_zero.expr())]
+        });
+
+        IsZeroWithOutputConfig { is_zero, output }
+    }
+
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        IsZeroChip::construct(self.config.is_zero.clone()).assign(region, offset, value)?;
+
+        let is_zero = value.map(|value| if value == F::zero() { F::one() } else { F::zero() });
+        region.assign_advice(|| "is zero", self.config.output, offset, || is_zero)
+    }
+}
+
+/// `N` is-zero checks sharing one gate and one `value_inv` column; the inverse of value `i`
+/// lives `i` rows below the row the gate is enabled on.
+///
+/// The values can sit in one row, but the inverses take `N` rows of `value_inv`, so the gate
+/// spans `N` rows and must be enabled at least `N` rows apart.
+#[derive(Clone, Debug)]
+pub struct IsZeroBatchConfig<F, const N: usize> {
+    pub value_inv: Column<Advice>,
+    pub is_zero_exprs: [Expression<F>; N],
+}
+
+impl<F: FieldExt, const N: usize> IsZeroBatchConfig<F, N> {
+    pub fn expr(&self, i: usize) -> Expression<F> {
+        self.is_zero_exprs[i].clone()
+    }
+
+    pub fn exprs(&self) -> [Expression<F>; N] {
+        self.is_zero_exprs.clone()
+    }
+}
+
+pub struct IsZeroBatchChip<F: FieldExt, const N: usize> {
+    config: IsZeroBatchConfig<F, N>,
+}
+
+impl<F: FieldExt, const N: usize> IsZeroBatchChip<F, N> {
+    pub fn construct(config: IsZeroBatchConfig<F, N>) -> Self {
+        IsZeroBatchChip { config }
+    }
+
+    /// `value_inv` is queried at rotations `0..N`; see `IsZeroBatchConfig` for the layout.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        values: impl FnOnce(&mut VirtualCells<'_, F>) -> [Expression<F>; N],
+        value_inv: Column<Advice>,
+    ) -> IsZeroBatchConfig<F, N> {
+        let mut is_zero_exprs = std::array::from_fn(|_| Expression::Constant(F::zero()));
+
+        meta.create_gate("batched is_zero", |meta| {
+            let values = values(meta);
+            let q_enable = q_enable(meta);
+
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let value_inv = meta.query_advice(value_inv, Rotation(i as i32));
+                    is_zero_exprs[i] = Expression::Constant(F::one()) - value.clone() * value_inv;
+                    q_enable.clone() * value * is_zero_exprs[i].clone()
+                })
+                .collect::<Vec<_>>()
+        });
+
+        IsZeroBatchConfig {
+            value_inv,
+            is_zero_exprs,
+        }
+    }
+
+    /// Assigns the inverse witnesses for `values`, where `offset` is the row the gate is
+    /// enabled on.
+    pub fn assign_many(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        values: [Value<F>; N],
+    ) -> Result<(), Error> {
+        for (i, value) in values.into_iter().enumerate() {
+            let value_inv = value.map(|value| value.invert().unwrap_or(F::zero()));
+            region.assign_advice(|| "value inv", self.config.value_inv, offset + i, || value_inv)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use crate::range_check::example2::RangeTableConfig;
+
+/// This chip compares two values in `0..RANGE` and returns the result as a boolean cell.
+///
+/// For `a, b` in `0..RANGE`, `d = b - a - strict + RANGE` lies in `0..2 * RANGE`, and
+/// `d >= RANGE` exactly when `a < b` (`strict = 1`) or `a <= b` (`strict = 0`). The chip
+/// witnesses `d = lt * RANGE + r` with `lt` boolean and looks up `a`, `b` and `r` in the
+/// range table from `range_check::example2`.
+///
+///       a   |   b   |  lt  |  r  |  strict  |  q_compare
+///     ------------------------------------------------------
+///       a   |   b   |  lt  |  r  |   0/1    |      1
+///
+#[derive(Debug, Clone)]
+pub struct LessThanConfig<F: FieldExt, const RANGE: usize> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    lt: Column<Advice>,
+    r: Column<Advice>,
+    strict: Column<Fixed>,
+    q_compare: Selector,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LessThanChip<F: FieldExt, const RANGE: usize> {
+    config: LessThanConfig<F, RANGE>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const RANGE: usize> Chip<F> for LessThanChip<F, RANGE> {
+    type Config = LessThanConfig<F, RANGE>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const RANGE: usize> LessThanChip<F, RANGE> {
+    pub fn construct(config: LessThanConfig<F, RANGE>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        table: RangeTableConfig<F, RANGE>,
+    ) -> LessThanConfig<F, RANGE> {
+        let lt = meta.advice_column();
+        let r = meta.advice_column();
+        let strict = meta.fixed_column();
+        let q_compare = meta.complex_selector();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(lt);
+
+        meta.create_gate("compare", |meta| {
+            let q = meta.query_selector(q_compare);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let lt = meta.query_advice(lt, Rotation::cur());
+            let r = meta.query_advice(r, Rotation::cur());
+            let strict = meta.query_fixed(strict, Rotation::cur());
+
+            let one = Expression::Constant(F::one());
+            let range = Expression::Constant(F::from(RANGE as u64));
+
+            Constraints::with_selector(
+                q,
+                [
+                    ("lt is boolean", lt.clone() * (one - lt.clone())),
+                    (
+                        "b - a - strict + RANGE = lt * RANGE + r",
+                        b - a - strict + range.clone() - (lt * range + r),
+                    ),
+                ],
+            )
+        });
+
+        for column in [a, b, r] {
+            meta.lookup(|meta| {
+                let q = meta.query_selector(q_compare);
+                let value = meta.query_advice(column, Rotation::cur());
+                vec![(q * value, table.value)]
+            });
+        }
+
+        LessThanConfig {
+            a,
+            b,
+            lt,
+            r,
+            strict,
+            q_compare,
+            table,
+        }
+    }
+
+    /// Returns a cell holding `1` if `a < b` and `0` otherwise.
+    pub fn lt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.compare(layouter, a, b, true)
+    }
+
+    /// Returns a cell holding `1` if `a <= b` and `0` otherwise.
+    pub fn le(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.compare(layouter, a, b, false)
+    }
+
+    /// Returns a cell holding `1` if `a > b` and `0` otherwise.
+    pub fn gt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.compare(layouter, b, a, true)
+    }
+
+    /// Returns a cell holding `1` if `a >= b` and `0` otherwise.
+    pub fn ge(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.compare(layouter, b, a, false)
+    }
+
+    fn compare(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        strict: bool,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || if strict { "a < b" } else { "a <= b" },
+            |mut region| {
+                self.config.q_compare.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                b.copy_advice(|| "b", &mut region, self.config.b, 0)?;
+                region.assign_fixed(
+                    || "strict",
+                    self.config.strict,
+                    0,
+                    || Value::known(F::from(strict)),
+                )?;
+
+                let lt = a.value().zip(b.value()).map(|(a, b)| {
+                    let (a, b) = (a.get_lower_128(), b.get_lower_128());
+                    F::from(if strict { a < b } else { a <= b })
+                });
+                // Out-of-range operands give a witness that fails the lookups.
+                let r = a.value().zip(b.value()).zip(lt).map(|((a, b), lt)| {
+                    let range = F::from(RANGE as u64);
+                    *b - a - F::from(strict) + range - lt * range
+                });
+
+                region.assign_advice(|| "r", self.config.r, 0, || r)?;
+                region.assign_advice(|| "lt", self.config.lt, 0, || lt)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::floor_planner::V1,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+    use proptest::prelude::*;
+
+    const RANGE: usize = 256;
+
+    #[derive(Debug, Clone)]
+    struct TestConfig<F: FieldExt> {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        less_than: LessThanConfig<F, RANGE>,
+    }
+
+    /// Exposes `a < b`, `a <= b`, `a > b` and `a >= b`, in that order.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        a: Value<F>,
+        b: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = TestConfig<F>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+
+            let a = meta.advice_column();
+            let b = meta.advice_column();
+            let table = RangeTableConfig::configure(meta);
+            let less_than = LessThanChip::configure(meta, a, b, table);
+
+            TestConfig {
+                input,
+                instance,
+                less_than,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.less_than.table.load(&mut layouter)?;
+            let chip = LessThanChip::construct(config.less_than);
+
+            let (a, b) = layouter.assign_region(
+                || "operands",
+                |mut region| {
+                    let a = region.assign_advice(|| "a", config.input, 0, || self.a)?;
+                    let b = region.assign_advice(|| "b", config.input, 1, || self.b)?;
+                    Ok((a, b))
+                },
+            )?;
+
+            let results = [
+                chip.lt(layouter.namespace(|| "lt"), &a, &b)?,
+                chip.le(layouter.namespace(|| "le"), &a, &b)?,
+                chip.gt(layouter.namespace(|| "gt"), &a, &b)?,
+                chip.ge(layouter.namespace(|| "ge"), &a, &b)?,
+            ];
+            for (row, result) in results.iter().enumerate() {
+                layouter.constrain_instance(result.cell(), config.instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run(a: u64, b: u64, expected: [bool; 4]) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        };
+        let public_input = expected.iter().map(|e| Fp::from(*e)).collect();
+        MockProver::run(9, &circuit, vec![public_input]).unwrap()
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn test_compare_matches_native(a in 0..RANGE as u64, b in 0..RANGE as u64) {
+            run(a, b, [a < b, a <= b, a > b, a >= b]).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_compare_edges() {
+        let max = RANGE as u64 - 1;
+        for (a, b) in [(0, 0), (0, 1), (1, 0), (0, max), (max, 0), (max, max)] {
+            run(a, b, [a < b, a <= b, a > b, a >= b]).assert_satisfied();
+        }
+
+        // A wrong claim is caught by the copy to the instance.
+        let prover = run(3, 5, [false, true, false, false]);
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|f| matches!(f, VerifyFailure::Permutation { .. })));
+
+        // Operands outside 0..RANGE are rejected by the lookups.
+        let prover = run(RANGE as u64, 0, [false, false, true, true]);
+        assert!(prover.verify().is_err());
+    }
+}
@@ -1,3 +1,14 @@
+mod bitwise;
+mod boolean;
 mod fibonacci;
+pub mod gadgets;
+mod is_equal;
 mod is_zero;
+mod less_than;
+mod min_max;
+mod piecewise;
 mod range_check;
+mod select;
+mod table_registry;
+mod util;
+mod zero_aggregate;
@@ -0,0 +1,245 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error},
+};
+
+use crate::less_than::{LessThanChip, LessThanConfig};
+use crate::range_check::example2::RangeTableConfig;
+use crate::select::{SelectChip, SelectConfig};
+
+/// This chip returns `min(a, b)`, `max(a, b)` and `clamp(x, lo, hi)` for values in
+/// `0..RANGE`, by comparing with `LessThanChip` and picking the result with `SelectChip`.
+///
+/// The comparison looks its operands up in the range table, so every input is also range
+/// checked, and so is every output.
+#[derive(Debug, Clone)]
+pub struct MinMaxConfig<F: FieldExt, const RANGE: usize> {
+    pub less_than: LessThanConfig<F, RANGE>,
+    select: SelectConfig,
+}
+
+pub struct MinMaxChip<F: FieldExt, const RANGE: usize> {
+    config: MinMaxConfig<F, RANGE>,
+}
+
+impl<F: FieldExt, const RANGE: usize> Chip<F> for MinMaxChip<F, RANGE> {
+    type Config = MinMaxConfig<F, RANGE>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const RANGE: usize> MinMaxChip<F, RANGE> {
+    pub fn construct(config: MinMaxConfig<F, RANGE>) -> Self {
+        Self { config }
+    }
+
+    /// `a` and `b` hold the operands for both the comparison and the selection.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        cond: Column<Advice>,
+        out: Column<Advice>,
+        table: RangeTableConfig<F, RANGE>,
+    ) -> MinMaxConfig<F, RANGE> {
+        let less_than = LessThanChip::configure(meta, a, b, table);
+        let select = SelectChip::configure(meta, cond, a, b, out);
+
+        MinMaxConfig { less_than, select }
+    }
+
+    /// Returns a cell holding the smaller of `a` and `b`.
+    pub fn min(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let lt = self.less_than().lt(layouter.namespace(|| "a < b"), a, b)?;
+        self.select()
+            .select(layouter.namespace(|| "min"), &lt, a, b)
+    }
+
+    /// Returns a cell holding the larger of `a` and `b`.
+    pub fn max(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let lt = self.less_than().lt(layouter.namespace(|| "a < b"), a, b)?;
+        self.select()
+            .select(layouter.namespace(|| "max"), &lt, b, a)
+    }
+
+    /// Returns a cell holding `x` bounded to `lo..=hi`, that is `min(max(x, lo), hi)`.
+    /// The bounds are not checked against each other; with `lo > hi` the result is `hi`.
+    pub fn clamp(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+        lo: &AssignedCell<F, F>,
+        hi: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let above_lo = self.max(layouter.namespace(|| "max(x, lo)"), x, lo)?;
+        self.min(layouter.namespace(|| "min(_, hi)"), &above_lo, hi)
+    }
+
+    fn less_than(&self) -> LessThanChip<F, RANGE> {
+        LessThanChip::construct(self.config.less_than.clone())
+    }
+
+    fn select(&self) -> SelectChip<F> {
+        SelectChip::construct(self.config.select.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{floor_planner::V1, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+    use proptest::prelude::*;
+
+    const RANGE: usize = 256;
+
+    #[derive(Debug, Clone)]
+    struct TestConfig<F: FieldExt> {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        min_max: MinMaxConfig<F, RANGE>,
+    }
+
+    /// Exposes `min(x, lo)`, `max(x, lo)` and `clamp(x, lo, hi)`, in that order.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        x: Value<F>,
+        lo: Value<F>,
+        hi: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = TestConfig<F>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+
+            let [a, b, cond, out] = [(); 4].map(|_| meta.advice_column());
+            let table = RangeTableConfig::configure(meta);
+            let min_max = MinMaxChip::configure(meta, a, b, cond, out, table);
+
+            TestConfig {
+                input,
+                instance,
+                min_max,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.min_max.less_than.table.load(&mut layouter)?;
+            let chip = MinMaxChip::construct(config.min_max);
+
+            let [x, lo, hi] = layouter.assign_region(
+                || "inputs",
+                |mut region| {
+                    let x = region.assign_advice(|| "x", config.input, 0, || self.x)?;
+                    let lo = region.assign_advice(|| "lo", config.input, 1, || self.lo)?;
+                    let hi = region.assign_advice(|| "hi", config.input, 2, || self.hi)?;
+                    Ok([x, lo, hi])
+                },
+            )?;
+
+            let results = [
+                chip.min(layouter.namespace(|| "min"), &x, &lo)?,
+                chip.max(layouter.namespace(|| "max"), &x, &lo)?,
+                chip.clamp(layouter.namespace(|| "clamp"), &x, &lo, &hi)?,
+            ];
+            for (row, result) in results.iter().enumerate() {
+                layouter.constrain_instance(result.cell(), config.instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run(x: u64, lo: u64, hi: u64, expected: [u64; 3]) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            x: Value::known(Fp::from(x)),
+            lo: Value::known(Fp::from(lo)),
+            hi: Value::known(Fp::from(hi)),
+        };
+        let public_input = expected.iter().map(|e| Fp::from(*e)).collect();
+        MockProver::run(9, &circuit, vec![public_input]).unwrap()
+    }
+
+    fn native(x: u64, lo: u64, hi: u64) -> [u64; 3] {
+        [x.min(lo), x.max(lo), x.max(lo).min(hi)]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn test_min_max_matches_native(
+            x in 0..RANGE as u64,
+            lo in 0..RANGE as u64,
+            hi in 0..RANGE as u64,
+        ) {
+            run(x, lo, hi, native(x, lo, hi)).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_min_max_edges() {
+        let max = RANGE as u64 - 1;
+        for (x, lo, hi) in [
+            (0, 0, 0),
+            (5, 5, 5),
+            (4, 5, 10),
+            (5, 5, 10),
+            (10, 5, 10),
+            (11, 5, 10),
+            (0, 0, max),
+            (max, 0, max),
+            (max, max, max),
+        ] {
+            run(x, lo, hi, native(x, lo, hi)).assert_satisfied();
+        }
+
+        // A wrong result is caught by the copy to the instance.
+        let prover = run(11, 5, 10, [5, 11, 11]);
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|f| matches!(f, VerifyFailure::Permutation { .. })));
+
+        // Inputs outside 0..RANGE are rejected by the comparison lookups.
+        let x = RANGE as u64;
+        let prover = run(x, 0, max, native(x, 0, max));
+        assert!(prover.verify().is_err());
+    }
+}
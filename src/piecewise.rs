@@ -0,0 +1,551 @@
+use std::ops::{Add, Mul, Sub};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Constraints, Error, Expression, Selector, VirtualCells,
+    },
+    poly::Rotation,
+};
+
+use crate::is_zero::{IsZeroChip, IsZeroConfig};
+use crate::range_check::example2::RangeTableConfig;
+
+/// An arithmetic expression over the inputs of a piecewise function.
+#[derive(Debug, Clone)]
+pub enum Expr<F> {
+    Input(usize),
+    Const(F),
+    Add(Box<Expr<F>>, Box<Expr<F>>),
+    Sub(Box<Expr<F>>, Box<Expr<F>>),
+    Mul(Box<Expr<F>>, Box<Expr<F>>),
+}
+
+impl<F: FieldExt> Expr<F> {
+    pub fn input(index: usize) -> Self {
+        Expr::Input(index)
+    }
+
+    pub fn constant(value: F) -> Self {
+        Expr::Const(value)
+    }
+
+    /// Evaluates the expression on native inputs.
+    pub fn evaluate(&self, inputs: &[F]) -> F {
+        match self {
+            Expr::Input(index) => inputs[*index],
+            Expr::Const(value) => *value,
+            Expr::Add(a, b) => a.evaluate(inputs) + b.evaluate(inputs),
+            Expr::Sub(a, b) => a.evaluate(inputs) - b.evaluate(inputs),
+            Expr::Mul(a, b) => a.evaluate(inputs) * b.evaluate(inputs),
+        }
+    }
+
+    /// Builds the expression over queried input cells.
+    pub fn expression(&self, inputs: &[Expression<F>]) -> Expression<F> {
+        match self {
+            Expr::Input(index) => inputs[*index].clone(),
+            Expr::Const(value) => Expression::Constant(*value),
+            Expr::Add(a, b) => a.expression(inputs) + b.expression(inputs),
+            Expr::Sub(a, b) => a.expression(inputs) - b.expression(inputs),
+            Expr::Mul(a, b) => a.expression(inputs) * b.expression(inputs),
+        }
+    }
+
+    fn max_input(&self) -> Option<usize> {
+        match self {
+            Expr::Input(index) => Some(*index),
+            Expr::Const(_) => None,
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) => a.max_input().max(b.max_input()),
+        }
+    }
+}
+
+impl<F> Add for Expr<F> {
+    type Output = Expr<F>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Expr::Add(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F> Sub for Expr<F> {
+    type Output = Expr<F>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Expr::Sub(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F> Mul for Expr<F> {
+    type Output = Expr<F>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Expr::Mul(Box::new(self), Box::new(rhs))
+    }
+}
+
+/// The condition guarding a branch.
+#[derive(Debug, Clone)]
+pub enum Condition<F> {
+    /// `lhs == rhs`, checked with `IsZeroChip`.
+    Eq(Expr<F>, Expr<F>),
+    /// `lhs < rhs` for operands in `0..RANGE`, checked with the range table.
+    Lt(Expr<F>, Expr<F>),
+}
+
+impl<F: FieldExt> Condition<F> {
+    /// Evaluates the condition on native inputs.
+    pub fn evaluate(&self, inputs: &[F]) -> bool {
+        let (lhs, rhs) = self.operands();
+        let (lhs, rhs) = (lhs.evaluate(inputs), rhs.evaluate(inputs));
+        match self {
+            Condition::Eq(_, _) => lhs == rhs,
+            Condition::Lt(_, _) => lhs.get_lower_128() < rhs.get_lower_128(),
+        }
+    }
+
+    fn operands(&self) -> (&Expr<F>, &Expr<F>) {
+        match self {
+            Condition::Eq(lhs, rhs) | Condition::Lt(lhs, rhs) => (lhs, rhs),
+        }
+    }
+}
+
+/// `if c_0 { e_0 } else if c_1 { e_1 } ... else { otherwise }` over `num_inputs` inputs.
+#[derive(Debug, Clone)]
+pub struct PiecewiseFunction<F> {
+    num_inputs: usize,
+    branches: Vec<(Condition<F>, Expr<F>)>,
+    otherwise: Expr<F>,
+}
+
+impl<F: FieldExt> PiecewiseFunction<F> {
+    pub fn builder(num_inputs: usize) -> PiecewiseBuilder<F> {
+        PiecewiseBuilder {
+            num_inputs,
+            branches: vec![],
+        }
+    }
+
+    /// Evaluates the function on native inputs.
+    pub fn evaluate(&self, inputs: &[F]) -> F {
+        for (condition, value) in self.branches.iter() {
+            if condition.evaluate(inputs) {
+                return value.evaluate(inputs);
+            }
+        }
+        self.otherwise.evaluate(inputs)
+    }
+}
+
+/// Builds a `PiecewiseFunction` one branch at a time, in order of precedence.
+#[derive(Debug, Clone)]
+pub struct PiecewiseBuilder<F> {
+    num_inputs: usize,
+    branches: Vec<(Condition<F>, Expr<F>)>,
+}
+
+impl<F: FieldExt> PiecewiseBuilder<F> {
+    /// Adds a branch taken when `condition` holds and no earlier branch was taken.
+    pub fn when(mut self, condition: Condition<F>, value: Expr<F>) -> Self {
+        self.branches.push((condition, value));
+        self
+    }
+
+    /// Finishes the function with the value taken when no branch matches.
+    pub fn otherwise(self, value: Expr<F>) -> PiecewiseFunction<F> {
+        let max_input = self
+            .branches
+            .iter()
+            .flat_map(|(condition, value)| {
+                let (lhs, rhs) = condition.operands();
+                [lhs.max_input(), rhs.max_input(), value.max_input()]
+            })
+            .chain([value.max_input()])
+            .max()
+            .flatten();
+        if let Some(index) = max_input {
+            assert!(
+                index < self.num_inputs,
+                "expression refers to an input past num_inputs"
+            );
+        }
+
+        PiecewiseFunction {
+            num_inputs: self.num_inputs,
+            branches: self.branches,
+            otherwise: value,
+        }
+    }
+}
+
+/// The helper cells behind a branch condition.
+#[derive(Debug, Clone)]
+enum Helper<F> {
+    Eq(IsZeroConfig<F>),
+    /// `rhs - lhs - 1 + RANGE = flag * RANGE + r`.
+    Lt {
+        r: Column<Advice>,
+    },
+}
+
+/// This chip proves `out = f(inputs)` for a `PiecewiseFunction` `f`, in a single row.
+///
+/// Each branch gets a boolean `flag` column, tied to its condition through an `IsZeroChip`
+/// inverse for `Eq` or a range-checked remainder for `Lt`, and the output is constrained to
+/// `out = flag_0 ? e_0 : (flag_1 ? e_1 : ... otherwise)`. `Lt` operands must lie in `0..RANGE`.
+///
+///      inputs  |  out  |  flag_i  |  helper_i  |  selector
+///     --------------------------------------------------------
+///     x_0 .. x_n | f(x) |  c_i(x)  | inv or r   |     1
+///
+#[derive(Debug, Clone)]
+pub struct PiecewiseConfig<F: FieldExt, const RANGE: usize> {
+    function: PiecewiseFunction<F>,
+    inputs: Vec<Column<Advice>>,
+    out: Column<Advice>,
+    flags: Vec<Column<Advice>>,
+    helpers: Vec<Helper<F>>,
+    selector: Selector,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+pub struct PiecewiseChip<F: FieldExt, const RANGE: usize> {
+    config: PiecewiseConfig<F, RANGE>,
+}
+
+impl<F: FieldExt, const RANGE: usize> Chip<F> for PiecewiseChip<F, RANGE> {
+    type Config = PiecewiseConfig<F, RANGE>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const RANGE: usize> PiecewiseChip<F, RANGE> {
+    pub fn construct(config: PiecewiseConfig<F, RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        function: PiecewiseFunction<F>,
+        table: RangeTableConfig<F, RANGE>,
+    ) -> PiecewiseConfig<F, RANGE> {
+        let selector = meta.complex_selector();
+        let inputs: Vec<_> = (0..function.num_inputs)
+            .map(|_| meta.advice_column())
+            .collect();
+        let out = meta.advice_column();
+
+        for column in inputs.iter().chain([&out]) {
+            meta.enable_equality(*column);
+        }
+
+        let query_inputs = |meta: &mut VirtualCells<'_, F>| {
+            inputs
+                .iter()
+                .map(|column| meta.query_advice(*column, Rotation::cur()))
+                .collect::<Vec<_>>()
+        };
+
+        let mut flags = vec![];
+        let mut helpers = vec![];
+        for (condition, _) in function.branches.iter() {
+            let (lhs, rhs) = condition.operands();
+
+            let helper = match condition {
+                Condition::Eq(_, _) => {
+                    let value_inv = meta.advice_column();
+                    Helper::Eq(IsZeroChip::configure(
+                        meta,
+                        |meta| meta.query_selector(selector),
+                        |meta| {
+                            let inputs = query_inputs(meta);
+                            lhs.expression(&inputs) - rhs.expression(&inputs)
+                        },
+                        value_inv,
+                    ))
+                }
+                Condition::Lt(_, _) => {
+                    let r = meta.advice_column();
+                    for operand in [lhs, rhs] {
+                        meta.lookup(|meta| {
+                            let q = meta.query_selector(selector);
+                            let inputs = query_inputs(meta);
+                            vec![(q * operand.expression(&inputs), table.value)]
+                        });
+                    }
+                    meta.lookup(|meta| {
+                        let q = meta.query_selector(selector);
+                        let r = meta.query_advice(r, Rotation::cur());
+                        vec![(q * r, table.value)]
+                    });
+                    Helper::Lt { r }
+                }
+            };
+
+            flags.push(meta.advice_column());
+            helpers.push(helper);
+        }
+
+        meta.create_gate("piecewise", |meta| {
+            let q = meta.query_selector(selector);
+            let inputs = query_inputs(meta);
+            let out = meta.query_advice(out, Rotation::cur());
+            let flags: Vec<_> = flags
+                .iter()
+                .map(|flag| meta.query_advice(*flag, Rotation::cur()))
+                .collect();
+
+            let one = Expression::Constant(F::one());
+            let range = Expression::Constant(F::from(RANGE as u64));
+
+            let mut constraints = vec![];
+            for (((condition, _), helper), flag) in function
+                .branches
+                .iter()
+                .zip(helpers.iter())
+                .zip(flags.iter())
+            {
+                match helper {
+                    Helper::Eq(is_zero) => constraints.push(flag.clone() - is_zero.expr()),
+                    Helper::Lt { r } => {
+                        let (lhs, rhs) = condition.operands();
+                        let r = meta.query_advice(*r, Rotation::cur());
+                        constraints.push(flag.clone() * (one.clone() - flag.clone()));
+                        constraints.push(
+                            rhs.expression(&inputs) - lhs.expression(&inputs) - one.clone()
+                                + range.clone()
+                                - (flag.clone() * range.clone() + r),
+                        );
+                    }
+                }
+            }
+
+            // Expands to `sum_i flag_i * prod_{j<i} (1 - flag_j) * value_i`, where the
+            // products select exactly one branch. Each level uses `rest` once, so the
+            // expression grows linearly with the number of branches.
+            let value = function.branches.iter().zip(flags.iter()).rev().fold(
+                function.otherwise.expression(&inputs),
+                |rest, ((_, value), flag)| {
+                    flag.clone() * value.expression(&inputs) + (one.clone() - flag.clone()) * rest
+                },
+            );
+            constraints.push(out - value);
+
+            Constraints::with_selector(q, constraints)
+        });
+
+        PiecewiseConfig {
+            function,
+            inputs,
+            out,
+            flags,
+            helpers,
+            selector,
+            table,
+        }
+    }
+
+    /// Returns a cell holding `f(inputs)`.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        assert_eq!(inputs.len(), config.inputs.len());
+
+        layouter.assign_region(
+            || "piecewise",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                for (cell, column) in inputs.iter().zip(config.inputs.iter()) {
+                    cell.copy_advice(|| "input", &mut region, *column, 0)?;
+                }
+
+                let values = inputs.iter().fold(Value::known(vec![]), |values, cell| {
+                    values.zip(cell.value()).map(|(mut values, value)| {
+                        values.push(*value);
+                        values
+                    })
+                });
+                let evaluate = |expr: &Expr<F>| values.clone().map(|values| expr.evaluate(&values));
+
+                for (((condition, _), helper), flag) in config
+                    .function
+                    .branches
+                    .iter()
+                    .zip(config.helpers.iter())
+                    .zip(config.flags.iter())
+                {
+                    let (lhs, rhs) = condition.operands();
+                    let (lhs, rhs) = (evaluate(lhs), evaluate(rhs));
+
+                    let flag_value = match helper {
+                        Helper::Eq(is_zero) => {
+                            let diff = lhs - rhs;
+                            IsZeroChip::construct(is_zero.clone()).assign(&mut region, 0, diff)?;
+                            diff.map(|diff| F::from(diff == F::zero()))
+                        }
+                        Helper::Lt { r } => {
+                            let range = F::from(RANGE as u64);
+                            let lt = lhs.zip(rhs).map(|(lhs, rhs)| {
+                                F::from(lhs.get_lower_128() < rhs.get_lower_128())
+                            });
+                            // Out-of-range operands give a remainder that fails the lookup.
+                            let r_value = lhs
+                                .zip(rhs)
+                                .zip(lt)
+                                .map(|((lhs, rhs), lt)| rhs - lhs - F::one() + range - lt * range);
+                            region.assign_advice(|| "r", *r, 0, || r_value)?;
+                            lt
+                        }
+                    };
+                    region.assign_advice(|| "flag", *flag, 0, || flag_value)?;
+                }
+
+                let out = values
+                    .clone()
+                    .map(|values| config.function.evaluate(&values));
+                region.assign_advice(|| "out", config.out, 0, || out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::floor_planner::V1,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    const RANGE: usize = 256;
+
+    /// `if a == b { c } else if a < b { b - a } else { a * c }`
+    fn function<F: FieldExt>() -> PiecewiseFunction<F> {
+        let [a, b, c] = [0, 1, 2].map(Expr::input);
+
+        PiecewiseFunction::builder(3)
+            .when(Condition::Eq(a.clone(), b.clone()), c.clone())
+            .when(Condition::Lt(a.clone(), b.clone()), b - a.clone())
+            .otherwise(a * c)
+    }
+
+    #[derive(Debug, Clone)]
+    struct TestConfig<F: FieldExt> {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        piecewise: PiecewiseConfig<F, RANGE>,
+    }
+
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        inputs: [Value<F>; 3],
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = TestConfig<F>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+
+            let table = RangeTableConfig::configure(meta);
+            let piecewise = PiecewiseChip::configure(meta, function(), table);
+
+            TestConfig {
+                input,
+                instance,
+                piecewise,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.piecewise.table.load(&mut layouter)?;
+            let chip = PiecewiseChip::construct(config.piecewise);
+
+            let inputs = layouter.assign_region(
+                || "inputs",
+                |mut region| {
+                    self.inputs
+                        .iter()
+                        .enumerate()
+                        .map(|(row, value)| {
+                            region.assign_advice(|| "input", config.input, row, || *value)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+
+            let out = chip.assign(layouter.namespace(|| "f"), &inputs)?;
+            layouter.constrain_instance(out.cell(), config.instance, 0)
+        }
+    }
+
+    fn run(inputs: [u64; 3], out: Fp) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            inputs: inputs.map(|x| Value::known(Fp::from(x))),
+        };
+        MockProver::run(9, &circuit, vec![vec![out]]).unwrap()
+    }
+
+    #[test]
+    fn test_piecewise_native() {
+        let f = function::<Fp>();
+        let eval = |inputs: [u64; 3]| f.evaluate(&inputs.map(Fp::from));
+
+        assert_eq!(eval([3, 3, 7]), Fp::from(7));
+        assert_eq!(eval([2, 5, 7]), Fp::from(3));
+        assert_eq!(eval([5, 2, 7]), Fp::from(35));
+    }
+
+    #[test]
+    fn test_piecewise_chip() {
+        let f = function::<Fp>();
+
+        // Every branch, including the edges of the comparison.
+        for a in [0, 1, 100, 255] {
+            for b in [0, 1, 100, 255] {
+                let inputs = [a, b, 9];
+                let out = f.evaluate(&inputs.map(Fp::from));
+
+                run(inputs, out).assert_satisfied();
+
+                let failures = run(inputs, out + Fp::one()).verify().unwrap_err();
+                assert!(failures
+                    .iter()
+                    .all(|f| matches!(f, VerifyFailure::Permutation { .. })));
+            }
+        }
+
+        // Comparison operands outside the range table are rejected.
+        let inputs = [256, 1, 9];
+        let out = f.evaluate(&inputs.map(Fp::from));
+        assert!(run(inputs, out).verify().is_err());
+    }
+}
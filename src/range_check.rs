@@ -1,5 +1,10 @@
-mod example1;
-mod example2;
-mod example3_broken;
+pub mod chip;
+pub mod decompose;
+pub mod example1;
+pub mod example2;
+pub mod example3;
 mod example4;
 mod example5;
+pub mod example6;
+pub mod interval;
+pub mod public_bound;
@@ -0,0 +1,346 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    plonk::{
+        Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Fixed,
+        Selector,
+    },
+    poly::Rotation,
+};
+
+use super::example2::RangeTableConfig;
+
+/// The way a given range is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// `v * (1 - v) * ... * (SMALL_RANGE - 1 - v) = 0`.
+    Expression,
+    /// `v` is looked up in a table of `0..LOOKUP_RANGE`.
+    Lookup,
+}
+
+#[derive(Debug, Clone)]
+/// A value constrained to `0..range` by the RangeCheckChip.
+pub struct RangeConstrained<F: FieldExt> {
+    cell: AssignedCell<Assigned<F>, F>,
+    range: usize,
+}
+
+impl<F: FieldExt> RangeConstrained<F> {
+    /// Returns the cell holding the range-constrained value.
+    pub fn cell(&self) -> &AssignedCell<Assigned<F>, F> {
+        &self.cell
+    }
+
+    /// Returns the witnessed value.
+    pub fn value(&self) -> Value<&Assigned<F>> {
+        self.cell.value()
+    }
+
+    /// Returns the exclusive upper bound the value was checked against.
+    pub fn range(&self) -> usize {
+        self.range
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RangeCheckConfig<F: FieldExt, const SMALL_RANGE: usize, const LOOKUP_RANGE: usize> {
+    value: Column<Advice>,
+    offset: Column<Fixed>,
+    q_small: Selector,
+    q_lookup: Selector,
+    pub table: RangeTableConfig<F, LOOKUP_RANGE>,
+}
+
+/// This chip checks that a witnessed value lies in `0..range`, where `range` is chosen by the
+/// caller at synthesis time.
+///
+/// Ranges up to `SMALL_RANGE` use the range-check expression from `example1`, larger ranges
+/// up to `LOOKUP_RANGE` use the lookup table from `example2`. Both check the value against
+/// their full width `R`; a range `r < R` is enforced by also checking `v + (R - r)`, with the
+/// offset `R - r` held in a fixed column.
+///
+///        value     |    offset     |  q_small  |  q_lookup  |
+///       -----------------------------------------------------
+///          v_0     |   SMALL - r_0 |     1     |     0      |
+///          v_1     |  LOOKUP - r_1 |     0     |     1      |
+#[derive(Debug, Clone)]
+pub struct RangeCheckChip<F: FieldExt, const SMALL_RANGE: usize, const LOOKUP_RANGE: usize> {
+    config: RangeCheckConfig<F, SMALL_RANGE, LOOKUP_RANGE>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const SMALL_RANGE: usize, const LOOKUP_RANGE: usize> Chip<F>
+    for RangeCheckChip<F, SMALL_RANGE, LOOKUP_RANGE>
+{
+    type Config = RangeCheckConfig<F, SMALL_RANGE, LOOKUP_RANGE>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const SMALL_RANGE: usize, const LOOKUP_RANGE: usize>
+    RangeCheckChip<F, SMALL_RANGE, LOOKUP_RANGE>
+{
+    pub fn construct(config: RangeCheckConfig<F, SMALL_RANGE, LOOKUP_RANGE>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the chip on `value`, using `table` for the lookup strategy.
+    ///
+    /// The table is passed in so that several chips can share it; the caller is responsible
+    /// for loading it once during synthesis.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        table: RangeTableConfig<F, LOOKUP_RANGE>,
+    ) -> RangeCheckConfig<F, SMALL_RANGE, LOOKUP_RANGE> {
+        assert!(SMALL_RANGE > 0 && SMALL_RANGE <= LOOKUP_RANGE);
+
+        let offset = meta.fixed_column();
+        let q_small = meta.selector();
+        let q_lookup = meta.complex_selector();
+
+        meta.enable_equality(value);
+
+        meta.create_gate("small range check", |meta| {
+            //        value     |    offset    |  q_small
+            //       ----------------------------------------
+            //          v       |   SMALL - r  |     1
+
+            let q = meta.query_selector(q_small);
+            let value = meta.query_advice(value, Rotation::cur());
+            let offset = meta.query_fixed(offset, Rotation::cur());
+
+            // Given a value v, returns the expression
+            // (v) * (1 - v) * (2 - v) * ... * (SMALL_RANGE - 1 - v)
+            let range_check = |value: Expression<F>| {
+                (1..SMALL_RANGE).fold(value.clone(), |expr, i| {
+                    expr * (Expression::Constant(F::from(i as u64)) - value.clone())
+                })
+            };
+
+            Constraints::with_selector(
+                q,
+                [
+                    ("value", range_check(value.clone())),
+                    ("value + offset", range_check(value + offset)),
+                ],
+            )
+        });
+
+        meta.lookup(|meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let value = meta.query_advice(value, Rotation::cur());
+
+            vec![(q_lookup * value, table.value)]
+        });
+
+        meta.lookup(|meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let value = meta.query_advice(value, Rotation::cur());
+            let offset = meta.query_fixed(offset, Rotation::cur());
+
+            vec![(q_lookup * (value + offset), table.value)]
+        });
+
+        RangeCheckConfig {
+            value,
+            offset,
+            q_small,
+            q_lookup,
+            table,
+        }
+    }
+
+    /// Returns the strategy used to check a value against `0..range`.
+    pub fn strategy(range: usize) -> Strategy {
+        assert!(
+            range > 0 && range <= LOOKUP_RANGE,
+            "range {} is not in 1..={}",
+            range,
+            LOOKUP_RANGE
+        );
+
+        if range <= SMALL_RANGE {
+            Strategy::Expression
+        } else {
+            Strategy::Lookup
+        }
+    }
+
+    /// Witnesses `value` and constrains it to `0..range`.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+        range: usize,
+    ) -> Result<RangeConstrained<F>, Error> {
+        let config = &self.config;
+
+        let (name, selector, width) = match Self::strategy(range) {
+            Strategy::Expression => (
+                "Assign value for simple range check",
+                config.q_small,
+                SMALL_RANGE,
+            ),
+            Strategy::Lookup => (
+                "Assign value for lookup range check",
+                config.q_lookup,
+                LOOKUP_RANGE,
+            ),
+        };
+
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                let offset = 0;
+
+                selector.enable(&mut region, offset)?;
+
+                region.assign_fixed(
+                    || "offset",
+                    config.offset,
+                    offset,
+                    || Value::known(F::from((width - range) as u64)),
+                )?;
+
+                let cell = region.assign_advice(|| "value", config.value, offset, || value)?;
+
+                Ok(RangeConstrained { cell, range })
+            },
+        )
+    }
+
+    /// Witnesses `value` and constrains it to `num_bits` bits.
+    pub fn assign_bits(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+        num_bits: usize,
+    ) -> Result<RangeConstrained<F>, Error> {
+        self.assign(layouter, value, 1 << num_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::floor_planner::V1,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::Circuit,
+    };
+
+    use super::*;
+
+    const SMALL_RANGE: usize = 8;
+    const LOOKUP_RANGE: usize = 256;
+
+    #[derive(Default)]
+    struct MyCircuit<F: FieldExt> {
+        checks: Vec<(Value<Assigned<F>>, usize)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = RangeCheckConfig<F, SMALL_RANGE, LOOKUP_RANGE>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                checks: self
+                    .checks
+                    .iter()
+                    .map(|(_, range)| (Value::unknown(), *range))
+                    .collect(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let table = RangeTableConfig::configure(meta);
+            RangeCheckChip::configure(meta, value, table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.table.load(&mut layouter)?;
+
+            let chip = RangeCheckChip::construct(config);
+            for (value, range) in self.checks.iter() {
+                chip.assign(layouter.namespace(|| "range check"), *value, *range)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn circuit(checks: &[(u64, usize)]) -> MyCircuit<Fp> {
+        MyCircuit {
+            checks: checks
+                .iter()
+                .map(|(value, range)| (Value::known(Fp::from(*value).into()), *range))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_strategy() {
+        type TestChip = RangeCheckChip<Fp, SMALL_RANGE, LOOKUP_RANGE>;
+
+        assert_eq!(TestChip::strategy(1), Strategy::Expression);
+        assert_eq!(TestChip::strategy(SMALL_RANGE), Strategy::Expression);
+        assert_eq!(TestChip::strategy(SMALL_RANGE + 1), Strategy::Lookup);
+        assert_eq!(TestChip::strategy(LOOKUP_RANGE), Strategy::Lookup);
+    }
+
+    #[test]
+    fn test_range_check_chip() {
+        let k = 9;
+
+        // Values just below the bound, for both strategies and for partial widths.
+        let valid = circuit(&[
+            (0, 1),
+            (4, 5),
+            (7, 8),
+            (0, 9),
+            (99, 100),
+            (200, 201),
+            (255, 256),
+        ]);
+        let prover = MockProver::run(k, &valid, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // Values at the bound fail the expression ...
+        for (value, range) in [(5, 5), (8, 8)] {
+            let prover = MockProver::run(k, &circuit(&[(value, range)]), vec![]).unwrap();
+            let failures = prover.verify().unwrap_err();
+            assert!(failures
+                .iter()
+                .all(|f| matches!(f, VerifyFailure::ConstraintNotSatisfied { .. })));
+        }
+
+        // ... and the lookup.
+        for (value, range) in [(9, 9), (100, 100), (256, 256)] {
+            let prover = MockProver::run(k, &circuit(&[(value, range)]), vec![]).unwrap();
+            let failures = prover.verify().unwrap_err();
+            assert!(failures
+                .iter()
+                .all(|f| matches!(f, VerifyFailure::Lookup { .. })));
+        }
+    }
+}
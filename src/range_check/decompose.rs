@@ -0,0 +1,310 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector, VirtualCells},
+    poly::Rotation,
+};
+
+use super::example2::RangeTableConfig;
+
+/// This chip checks that a value fits in `num_bits` bits, for any `num_bits` up to 128, by
+/// decomposing it into `K`-bit windows, where `RANGE = 2^K` is the size of the range table.
+///
+/// The running sum starts at `z_0 = v` and drops one window per row,
+/// `z_{i+1} = (z_i - w_i) / 2^K`, so each window is `w_i = z_i - 2^K * z_{i+1}` and is
+/// looked up in the table. After `ceil(num_bits / K)` windows the running sum must be `0`.
+///
+/// When `K` does not divide `num_bits`, the final window only has `s = num_bits % K` bits.
+/// It is looked up a second time multiplied by the fixed `shift = 2^(K - s)`, which fits
+/// in the table only if `w < 2^s`; on full windows `shift` is `1`.
+///
+///       z     |  shift  |  q_window
+///     ----------------------------------
+///      z_0    |    1    |     1
+///      z_1    |    1    |     1
+///      ...    |   ...   |    ...
+///    z_{n-1}  | 2^(K-s) |     1
+///      0      |         |     0
+///
+/// A check costs `ceil(num_bits / K) + 1` rows.
+#[derive(Debug, Clone)]
+pub struct DecomposeConfig<F: FieldExt, const RANGE: usize> {
+    z: Column<Advice>,
+    shift: Column<Fixed>,
+    q_window: Selector,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecomposeChip<F: FieldExt, const RANGE: usize> {
+    config: DecomposeConfig<F, RANGE>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const RANGE: usize> Chip<F> for DecomposeChip<F, RANGE> {
+    type Config = DecomposeConfig<F, RANGE>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const RANGE: usize> DecomposeChip<F, RANGE> {
+    /// Bits per window.
+    pub const K: usize = RANGE.trailing_zeros() as usize;
+
+    pub fn construct(config: DecomposeConfig<F, RANGE>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        z: Column<Advice>,
+        table: RangeTableConfig<F, RANGE>,
+    ) -> DecomposeConfig<F, RANGE> {
+        assert!(
+            RANGE > 1 && RANGE.is_power_of_two(),
+            "RANGE must be a power of two"
+        );
+
+        let shift = meta.fixed_column();
+        let constants = meta.fixed_column();
+        let q_window = meta.complex_selector();
+
+        meta.enable_equality(z);
+        meta.enable_constant(constants);
+
+        let window = |meta: &mut VirtualCells<'_, F>| {
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            z_cur - z_next * Expression::Constant(F::from(RANGE as u64))
+        };
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_window);
+            let w = window(meta);
+
+            vec![(q * w, table.value)]
+        });
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_window);
+            let w = window(meta);
+            let shift = meta.query_fixed(shift, Rotation::cur());
+
+            vec![(q * w * shift, table.value)]
+        });
+
+        DecomposeConfig {
+            z,
+            shift,
+            q_window,
+            table,
+        }
+    }
+
+    /// Witnesses `value` and constrains it to `num_bits` bits.
+    pub fn witness_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || format!("{} bit range check", num_bits),
+            |mut region| {
+                let z_0 = region.assign_advice(|| "z_0", self.config.z, 0, || value)?;
+                self.decompose(&mut region, value, num_bits)?;
+                Ok(z_0)
+            },
+        )
+    }
+
+    /// Constrains an existing cell to `num_bits` bits.
+    pub fn copy_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || format!("{} bit range check", num_bits),
+            |mut region| {
+                value.copy_advice(|| "z_0", &mut region, self.config.z, 0)?;
+                self.decompose(&mut region, value.value().copied(), num_bits)
+            },
+        )
+    }
+
+    /// Assigns the windows of `z_0`, already in row 0, and constrains the final running sum
+    /// to zero.
+    fn decompose(
+        &self,
+        region: &mut Region<'_, F>,
+        z_0: Value<F>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        assert!(
+            num_bits > 0 && num_bits <= 128,
+            "num_bits {} is not in 1..=128",
+            num_bits
+        );
+
+        let k = Self::K;
+        let num_windows = num_bits.div_ceil(k);
+        let short_bits = num_bits % k;
+
+        let mask = (RANGE - 1) as u128;
+        let inv_range = F::from(RANGE as u64).invert().unwrap();
+
+        let mut z = z_0;
+        let mut z_cell = None;
+        for i in 0..num_windows {
+            self.config.q_window.enable(region, i)?;
+
+            let shift = if i == num_windows - 1 && short_bits != 0 {
+                1u64 << (k - short_bits)
+            } else {
+                1
+            };
+            region.assign_fixed(
+                || "shift",
+                self.config.shift,
+                i,
+                || Value::known(F::from(shift)),
+            )?;
+
+            // Computed in the field rather than on the integer, so that a value that does
+            // not fit still gets a consistent witness and fails on the final running sum.
+            z = z.map(|z| (z - F::from_u128(z.get_lower_128() & mask)) * inv_range);
+            z_cell = Some(region.assign_advice(
+                || format!("z_{}", i + 1),
+                self.config.z,
+                i + 1,
+                || z,
+            )?);
+        }
+
+        region.constrain_constant(z_cell.unwrap().cell(), F::zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::floor_planner::V1,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::Circuit,
+    };
+
+    const RANGE: usize = 256;
+
+    #[derive(Debug, Clone)]
+    struct TestConfig<F: FieldExt> {
+        input: Column<Advice>,
+        decompose: DecomposeConfig<F, RANGE>,
+    }
+
+    /// Checks `value` against `num_bits` bits, once witnessed by the chip and once copied in.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        value: Value<F>,
+        num_bits: usize,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = TestConfig<F>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                num_bits: self.num_bits,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            meta.enable_equality(input);
+
+            let z = meta.advice_column();
+            let table = RangeTableConfig::configure(meta);
+            let decompose = DecomposeChip::configure(meta, z, table);
+
+            TestConfig { input, decompose }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.decompose.table.load(&mut layouter)?;
+            let chip = DecomposeChip::construct(config.decompose);
+
+            chip.witness_check(layouter.namespace(|| "witness"), self.value, self.num_bits)?;
+
+            let value = layouter.assign_region(
+                || "value",
+                |mut region| region.assign_advice(|| "value", config.input, 0, || self.value),
+            )?;
+            chip.copy_check(layouter.namespace(|| "copy"), &value, self.num_bits)
+        }
+    }
+
+    fn run(value: Fp, num_bits: usize) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            value: Value::known(value),
+            num_bits,
+        };
+        MockProver::run(9, &circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn test_decompose_widths() {
+        for num_bits in [16, 32, 64, 128] {
+            let max = Fp::from_u128(u128::MAX >> (128 - num_bits));
+
+            run(Fp::zero(), num_bits).assert_satisfied();
+            run(Fp::one(), num_bits).assert_satisfied();
+            run(max, num_bits).assert_satisfied();
+
+            // 2^num_bits leaves a non-zero running sum.
+            let failures = run(max + Fp::one(), num_bits).verify().unwrap_err();
+            assert!(failures
+                .iter()
+                .all(|f| matches!(f, VerifyFailure::Permutation { .. })));
+        }
+
+        // The field element -1 does not fit in 128 bits.
+        assert!(run(-Fp::one(), 128).verify().is_err());
+    }
+
+    #[test]
+    fn test_decompose_short_window() {
+        // 12 bits with 8-bit windows leaves a 4-bit final window.
+        for num_bits in [1, 4, 12, 20, 33] {
+            let max = (1u64 << num_bits) - 1;
+
+            run(Fp::from(max), num_bits).assert_satisfied();
+
+            // 2^num_bits passes the full-width lookups but not the shifted one.
+            let failures = run(Fp::from(max + 1), num_bits).verify().unwrap_err();
+            assert!(failures
+                .iter()
+                .all(|f| matches!(f, VerifyFailure::Lookup { .. })));
+        }
+    }
+}
@@ -16,10 +16,22 @@ use halo2_proofs::{
 
 #[derive(Debug, Clone)]
 /// A range-constrained value in the circuit produced by the RangeCheckConfig.
-struct RangeConstrained<F: FieldExt, const RANGE: usize>(AssignedCell<Assigned<F>, F>);
+pub struct RangeConstrained<F: FieldExt, const RANGE: usize>(AssignedCell<Assigned<F>, F>);
+
+impl<F: FieldExt, const RANGE: usize> RangeConstrained<F, RANGE> {
+    /// Returns the cell holding the range-constrained value.
+    pub fn cell(&self) -> &AssignedCell<Assigned<F>, F> {
+        &self.0
+    }
+
+    /// Returns the witnessed value, which lies in `0..RANGE`.
+    pub fn value(&self) -> Value<&Assigned<F>> {
+        self.0.value()
+    }
+}
 
 #[derive(Debug, Clone)]
-struct RangeCheckConfig<F: FieldExt, const RANGE: usize> {
+pub struct RangeCheckConfig<F: FieldExt, const RANGE: usize> {
     value: Column<Advice>,
     q_range_check: Selector,
     _marker: PhantomData<F>,
@@ -6,7 +6,7 @@ use halo2_proofs::{
 };
 
 mod table;
-use table::*;
+pub use table::RangeTableConfig;
 
 /// This helper checks that the value witnessed in a given cell is within a given range.
 /// Depending on the range, this helper uses either a range-check expression (for small ranges),
@@ -20,23 +20,45 @@ use table::*;
 
 #[derive(Debug, Clone)]
 /// A range-constrained value in the circuit produced by the RangeCheckConfig.
-struct RangeConstrained<F: FieldExt, const RANGE: usize>(AssignedCell<Assigned<F>, F>);
+pub struct RangeConstrained<F: FieldExt, const RANGE: usize>(AssignedCell<Assigned<F>, F>);
+
+impl<F: FieldExt, const RANGE: usize> RangeConstrained<F, RANGE> {
+    /// Returns the cell holding the range-constrained value.
+    pub fn cell(&self) -> &AssignedCell<Assigned<F>, F> {
+        &self.0
+    }
+
+    /// Returns the witnessed value, which lies in `0..RANGE`.
+    pub fn value(&self) -> Value<&Assigned<F>> {
+        self.0.value()
+    }
+}
 
 #[derive(Debug, Clone)]
-struct RangeCheckConfig<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> {
+pub struct RangeCheckConfig<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> {
     q_range_check: Selector,
     q_lookup: Selector,
     value: Column<Advice>,
-    table: RangeTableConfig<F, LOOKUP_RANGE>,
+    pub table: RangeTableConfig<F, LOOKUP_RANGE>,
 }
 
 impl<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize>
     RangeCheckConfig<F, RANGE, LOOKUP_RANGE>
 {
     pub fn configure(meta: &mut ConstraintSystem<F>, value: Column<Advice>) -> Self {
+        let table = RangeTableConfig::configure(meta);
+        Self::configure_with_table(meta, value, table)
+    }
+
+    /// Like `configure`, but looks values up in an existing table, so that several configs
+    /// can share one.
+    pub fn configure_with_table(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        table: RangeTableConfig<F, LOOKUP_RANGE>,
+    ) -> Self {
         let q_range_check = meta.selector();
         let q_lookup = meta.complex_selector();
-        let table = RangeTableConfig::configure(meta);
 
         meta.create_gate("range check", |meta| {
             //        value     |    q_range_check
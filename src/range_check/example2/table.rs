@@ -8,22 +8,25 @@ use halo2_proofs::{
 
 /// A lookup table of values from 0..RANGE.
 #[derive(Debug, Clone)]
-pub(super) struct RangeTableConfig<F: FieldExt, const RANGE: usize> {
-    pub(super) value: TableColumn,
+pub struct RangeTableConfig<F: FieldExt, const RANGE: usize> {
+    pub value: TableColumn,
     _marker: PhantomData<F>,
 }
 
 impl<F: FieldExt, const RANGE: usize> RangeTableConfig<F, RANGE> {
-    pub(super) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
-        let value = meta.lookup_table_column();
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        Self::from_column(meta.lookup_table_column())
+    }
 
+    /// Wraps a column allocated elsewhere, e.g. shared through a `TableRegistry`.
+    pub(crate) fn from_column(value: TableColumn) -> Self {
         Self {
             value,
             _marker: PhantomData,
         }
     }
 
-    pub(super) fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         layouter.assign_table(
             || "load range-check table",
             |mut table| {
@@ -1,15 +1,12 @@
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{AssignedCell, Layouter, Value},
-    plonk::{
-        Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Selector,
-        TableColumn,
-    },
+    plonk::{Advice, Assigned, Column, ConstraintSystem, Error, Selector},
     poly::Rotation,
 };
 
 mod table;
-use table::*;
+pub use table::RangeTableConfig;
 
 /// This helper uses a lookup table to check that the value witnessed in a given cell is
 /// within a given range.
@@ -18,7 +15,7 @@ use table::*;
 ///
 ///        value     |   q_lookup  |  table_num_bits  |  table_value  |
 ///       -------------------------------------------------------------
-///          v_0     |      0      |        1         |       0       |
+///          v_0     |      0      |        0         |       0       |
 ///          v_1     |      1      |        1         |       1       |
 ///          ...     |     ...     |        2         |       2       |
 ///          ...     |     ...     |        2         |       3       |
@@ -26,20 +23,41 @@ use table::*;
 ///
 /// We use a K-bit lookup table, that is tagged 1..=K, where the tag `i` marks an `i`-bit value.
 ///
+/// Both lookup inputs are multiplied by `q_lookup`, so rows where it is disabled look up
+/// `(0, 0)`. The table starts with that default row, which is the only row for `0`: zero is
+/// a 0-bit value, and any other tag for it fails the lookup.
+///
 
 #[derive(Debug, Clone)]
 /// A range-constrained value in the circuit produced by the RangeCheckConfig.
-struct RangeConstrained<F: FieldExt> {
+pub struct RangeConstrained<F: FieldExt> {
     num_bits: AssignedCell<Assigned<F>, F>,
     assigned_cell: AssignedCell<Assigned<F>, F>,
 }
 
+impl<F: FieldExt> RangeConstrained<F> {
+    /// Returns the cell holding the bit length the value was checked against.
+    pub fn num_bits(&self) -> &AssignedCell<Assigned<F>, F> {
+        &self.num_bits
+    }
+
+    /// Returns the cell holding the range-constrained value.
+    pub fn cell(&self) -> &AssignedCell<Assigned<F>, F> {
+        &self.assigned_cell
+    }
+
+    /// Returns the witnessed value.
+    pub fn value(&self) -> Value<&Assigned<F>> {
+        self.assigned_cell.value()
+    }
+}
+
 #[derive(Debug, Clone)]
-struct RangeCheckConfig<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> {
+pub struct RangeCheckConfig<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> {
     q_lookup: Selector,
     num_bits: Column<Advice>,
     value: Column<Advice>,
-    table: RangeTableConfig<F, NUM_BITS, RANGE>,
+    pub table: RangeTableConfig<F, NUM_BITS, RANGE>,
 }
 
 impl<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> RangeCheckConfig<F, NUM_BITS, RANGE> {
@@ -56,12 +74,9 @@ impl<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> RangeCheckConfig<F,
             let num_bits = meta.query_advice(num_bits, Rotation::cur());
             let value = meta.query_advice(value, Rotation::cur());
 
-            // THIS IS BROKEN!!!!!!
-            // Hint: consider the case where q_lookup = 0. What are our input expressions to the lookup argument then?
-            
-            // let num_bits = 
+            // When q_lookup = 0 both inputs are 0, which matches the table's default row.
             vec![
-                (q_lookup.clone() * num_bits, table.num_bits ),
+                (q_lookup.clone() * num_bits, table.num_bits),
                 (q_lookup * value, table.value),
             ]
         });
@@ -116,7 +131,7 @@ mod tests {
         circuit::floor_planner::V1,
         dev::{FailureLocation, MockProver, VerifyFailure},
         pasta::Fp,
-        plonk::{Any, Circuit},
+        plonk::Circuit,
     };
 
     use super::*;
@@ -178,6 +193,36 @@ mod tests {
                 prover.assert_satisfied();
             }
         }
+
+        // Zero only matches the default row
+        let circuit = MyCircuit::<Fp, NUM_BITS, RANGE> {
+            num_bits: Value::known(0),
+            value: Value::known(Fp::zero().into()),
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // Wrong `num_bits` for the value, including a 1-bit zero, and values that do not fit
+        // in any tag
+        for (num_bits, value) in [(3, 8), (2, 1), (8, 127), (0, 1), (1, 0), (8, 256), (9, 256)] {
+            let circuit = MyCircuit::<Fp, NUM_BITS, RANGE> {
+                num_bits: Value::known(num_bits),
+                value: Value::known(Fp::from(value).into()),
+            };
+
+            let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+            assert_eq!(
+                prover.verify(),
+                Err(vec![VerifyFailure::Lookup {
+                    lookup_index: 0,
+                    location: FailureLocation::InRegion {
+                        region: (1, "Assign value").into(),
+                        offset: 0
+                    }
+                }])
+            );
+        }
     }
 
     // #[cfg(feature = "dev-graph")]
@@ -9,15 +9,17 @@ use halo2_proofs::{
 /// A lookup table of values up to RANGE
 /// e.g. RANGE = 256, values = [0..255]
 /// This table is tagged by an index `k`, where `k` is the number of bits of the element in the `value` column.
+/// Its first row is the default `(0, 0)`, which disabled lookups resolve to; it is also the
+/// only row for `0`, so every value has exactly one tag and `0` is a 0-bit value.
 #[derive(Debug, Clone)]
-pub(super) struct RangeTableConfig<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> {
-    pub(super) num_bits: TableColumn,
-    pub(super) value: TableColumn,
+pub struct RangeTableConfig<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> {
+    pub num_bits: TableColumn,
+    pub value: TableColumn,
     _marker: PhantomData<F>,
 }
 
 impl<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> RangeTableConfig<F, NUM_BITS, RANGE> {
-    pub(super) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
         assert_eq!(1 << NUM_BITS, RANGE);
 
         let num_bits = meta.lookup_table_column();
@@ -30,19 +32,19 @@ impl<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> RangeTableConfig<F,
         }
     }
 
-    pub(super) fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         layouter.assign_table(
             || "load range-check table",
             |mut table| {
                 let mut offset = 0;
 
-                // Assign (num_bits = 1, value = 0)
+                // Assign the default row (num_bits = 0, value = 0)
                 {
                     table.assign_cell(
                         || "assign num_bits",
                         self.num_bits,
                         offset,
-                        || Value::known(F::one()),
+                        || Value::known(F::zero()),
                     )?;
                     table.assign_cell(
                         || "assign value",
@@ -70,7 +72,6 @@ impl<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> RangeTableConfig<F,
                         )?;
                         offset += 1;
                     }
-
                 }
 
                 Ok(())
@@ -1,202 +1,237 @@
-// Goal: This is an extension of example5 that performs the range check using lookup table 
-// It includes a further lookup table that contains a value num_bits. 
+// Goal: This is an extension of example5 that performs the range check using lookup table
+// It includes a further lookup table that contains a value num_bits.
 // For example it can be that our range is 8 bits, but we want to perform a range check on 4 bits.
 // That's why we need this optimization.
 
-use std::marker::PhantomData;
-
 use halo2_proofs::{
-    plonk::*,
-    circuit::*,
-    arithmetic::FieldExt, poly::Rotation
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
 };
 
 // create a submodule which is my table and use that
 mod table;
-use table::RangeCheckTable;
+pub use table::RangeCheckTable;
+
+/// This helper checks a value against a lookup table tagged by bit length, and returns the
+/// bit length alongside the value. The bit length is computed by the chip from the witness,
+/// so callers cannot pass one that disagrees with the value; `assert_bit_length` then pins
+/// it to an expected value.
+///
+/// Ranges up to `RANGE` are also checked with the range-check expression.
+///
+///        value     |   num_bits   |  q_range_check  |  q_lookup  |
+///       ---------------------------------------------------------
+///          v_0     |  bits(v_0)   |        1        |     1      |
+///          v_1     |  bits(v_1)   |        0        |     1      |
+///
+/// The bit length of `0` is `0`, matched by the table's default row. Each value has a
+/// single row in the table, so a `num_bits` witness other than its bit length fails the
+/// lookup.
+///
 
 #[derive(Debug, Clone)]
 /// A range-constrained value in the circuit produced by the RangeCheckConfig.
-struct RangeConstrained<F: FieldExt, const RANGE: usize>(AssignedCell<Assigned<F>, F>);
+pub struct RangeConstrained<F: FieldExt> {
+    num_bits: AssignedCell<Assigned<F>, F>,
+    assigned_cell: AssignedCell<Assigned<F>, F>,
+}
 
-#[derive(Debug, Clone)]
+impl<F: FieldExt> RangeConstrained<F> {
+    /// Returns the cell holding the bit length of the value.
+    pub fn num_bits(&self) -> &AssignedCell<Assigned<F>, F> {
+        &self.num_bits
+    }
+
+    /// Returns the cell holding the range-constrained value.
+    pub fn cell(&self) -> &AssignedCell<Assigned<F>, F> {
+        &self.assigned_cell
+    }
+
+    /// Returns the witnessed value.
+    pub fn value(&self) -> Value<&Assigned<F>> {
+        self.assigned_cell.value()
+    }
+}
 
 // WE ADD A FURTHER NUM_BITS COLUMN TO OUR CONFIG
-struct RangeCheckConfig<F:FieldExt, const RANGE: usize, const LOOKUP_NUMBITS: usize, const LOOKUP_RANGE: usize> {
+#[derive(Debug, Clone)]
+pub struct RangeCheckConfig<
+    F: FieldExt,
+    const RANGE: usize,
+    const LOOKUP_NUMBITS: usize,
+    const LOOKUP_RANGE: usize,
+> {
     value: Column<Advice>,
     num_bits: Column<Advice>,
     q_range_check: Selector,
     q_lookup: Selector,
-    table: RangeCheckTable<F, LOOKUP_NUMBITS, LOOKUP_RANGE>
+    pub table: RangeCheckTable<F, LOOKUP_NUMBITS, LOOKUP_RANGE>,
 }
 
-// Write the gate for our range check Config
 // It's good practive to pass advice columns to the config (rather than creating it within the config)
 // because these are very likely to be shared across multiple config
-impl<F: FieldExt, const RANGE: usize, const LOOKUP_NUMBITS: usize, const LOOKUP_RANGE: usize> RangeCheckConfig<F, RANGE, LOOKUP_NUMBITS, LOOKUP_RANGE> {
-
-    // REMEMBER THAT THE CONFIGURATION HAPPEN AT KEYGEN TIME
-    fn configure(
+impl<F: FieldExt, const RANGE: usize, const LOOKUP_NUMBITS: usize, const LOOKUP_RANGE: usize>
+    RangeCheckConfig<F, RANGE, LOOKUP_NUMBITS, LOOKUP_RANGE>
+{
+    pub fn configure(
         meta: &mut ConstraintSystem<F>,
         value: Column<Advice>,
-        num_bits: Column<Advice> 
+        num_bits: Column<Advice>,
     ) -> Self {
         // Toggles the range check constraint
         let q_range_check = meta.selector();
 
         // Toggles the lookup argument
-        // I use a complex selector for that. 
         // Simple selector cannot appear in lookup arguments.
         let q_lookup = meta.complex_selector();
 
-        // We also need to configure our look up table and pass it to config
-        let table =   RangeCheckTable::configure(meta);
+        let table = RangeCheckTable::configure(meta);
 
-        let config = Self {
-            q_range_check,
-            q_lookup,
-            value,
-            num_bits,
-            table: table.clone()
-        }; 
+        // `assert_bit_length` copies num_bits and fixes it to a constant
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+        meta.enable_equality(num_bits);
 
         // range-check gate
         // For a value v and a range R, check that v < R
-        // v * (1 - v) * (2 - v) * ... (R - 1 - v) = 0 if v is any of these values! 
+        // v * (1 - v) * (2 - v) * ... (R - 1 - v) = 0 if v is any of these values!
         meta.create_gate("range check", |meta| {
             let q_range_check = meta.query_selector(q_range_check);
-            // note that we don't need to specify the rotation when querying the selctor
-            // That's because the selector always get queried at the current row
-            // While the advice columns get queried relatively to the selector offset, so we need to specify the relative rotation
-            let value = meta.query_advice(value,Rotation::cur());
+            let value = meta.query_advice(value, Rotation::cur());
 
-            // This is a closure that produce the expression defined previously 
             let range_check = |range: usize, value: Expression<F>| {
-                (0..range).fold(value.clone(), |expr: Expression<F>, i: usize| {
+                (1..range).fold(value.clone(), |expr, i| {
                     expr * (Expression::Constant(F::from(i as u64)) - value.clone())
                 })
             };
-            // This is a way to return the constrain from our create_gate function. 
-            // similar to what we were doing previously using "vec![s * (a + b - c)]"
-            // this api, behind the scene, multiplies the specified constraint by the selector
+
             Constraints::with_selector(q_range_check, [("range check", range_check(RANGE, value))])
         });
 
-        // range-check using lookup argument
-        // Check that a value is contained within a lookup table of values 0..RANGE (exclusive)
-        // IN THIS EXAMPLE we also want to lookup into the num_bits table
-        // api to instantiate a lookup argument
-        // Similar to create gate as an api so we need to query the selector and our value
+        // Check that (num_bits, value) is a row of the tagged table, so num_bits is the
+        // bit length of the value.
         meta.lookup(|meta| {
             let q_lookup = meta.query_selector(q_lookup);
-            // we add another advise column from the previous example
             let num_bits = meta.query_advice(num_bits, Rotation::cur());
-            let value = meta.query_advice(value,Rotation::cur());
+            let value = meta.query_advice(value, Rotation::cur());
 
-            // The meta.lookup api expect to return a vector of tuples, where the first element
-            // is what you are looking at, and the second element is the corresponding table we are looking into
-            // NOW WE ALSO LOOKUP num_bits from the instance column inside the num_bits lookup table
+            // When q_lookup = 0 both inputs are 0, which matches the table's default row.
             vec![
                 (q_lookup.clone() * value, table.value),
-                (q_lookup * num_bits, table.num_bits)
+                (q_lookup * num_bits, table.num_bits),
             ]
         });
 
-        config
+        Self {
+            value,
+            num_bits,
+            q_range_check,
+            q_lookup,
+            table,
+        }
     }
 
-    // assign value to each cell inside the advise column
-    // we can modify this assign function such that under a certain range enables the simple range check expression
-    // and over a certain range enables the look up argument
-    // the range passed in is the actual claimed range
-    fn assign(
+    /// Assigns `value` and its bit length, which is always looked up in the table. When the
+    /// claimed `range` is at most `RANGE`, the value is also checked with the range-check
+    /// expression.
+    pub fn assign(
         &self,
         mut layouter: impl Layouter<F>,
         value: Value<Assigned<F>>,
-        num_bits: usize,
-        range: usize
-    ) -> Result<(), Error> {
-
+        range: usize,
+    ) -> Result<RangeConstrained<F>, Error> {
         assert!(range <= LOOKUP_RANGE);
 
-        if range < RANGE {
-            layouter.assign_region(|| "Assign value", |mut region| {
-
+        layouter.assign_region(
+            || "Assign value",
+            |mut region| {
                 let offset = 0;
-    
-                // Enable q range check
-                self.q_range_check.enable(&mut region, offset)?;
-
-                // assign num bits
-                region.assign_advice(
-                    || "assign num_bits",
-                    self.num_bits,
-                    offset,
-                    || Value::known(F::from(num_bits as u64))
-                )?;
 
-                // assign given value and return RangeConstrained struct
-                region.assign_advice(
-                    || "assign value",
-                    self.value,
-                    offset,
-                    || value
-                )?;
-
-                Ok(())
-            })
-        } else {
-            layouter.assign_region(|| "Assign value for lookup range check", |mut region| {
-
-                let offset = 0;
-    
-                // Enable q range check
+                // The lookup ties num_bits to the value, so it is enabled in both cases
                 self.q_lookup.enable(&mut region, offset)?;
+                if range <= RANGE {
+                    self.q_range_check.enable(&mut region, offset)?;
+                }
 
-                // assign given value and return RangeConstrained struct
-                region.assign_advice(
-                    || "assign value",
-                    self.value,
+                // Assign num_bits, computed from the value
+                let num_bits = region.assign_advice(
+                    || "assign num_bits",
+                    self.num_bits,
                     offset,
-                    || value
+                    || value.map(|v| Assigned::from(F::from(Self::bit_length(&v)))),
                 )?;
 
-                Ok(())
-        })
+                // Assign value
+                let assigned_cell =
+                    region.assign_advice(|| "assign value", self.value, offset, || value)?;
 
-}
+                Ok(RangeConstrained {
+                    num_bits,
+                    assigned_cell,
+                })
+            },
+        )
+    }
 
-}
+    /// Constrains the bit length returned by `assign` to equal `n`.
+    pub fn assert_bit_length(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num_bits: &AssignedCell<Assigned<F>, F>,
+        n: usize,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "Assert bit length",
+            |mut region| {
+                let num_bits =
+                    num_bits.copy_advice(|| "num_bits", &mut region, self.num_bits, 0)?;
+                region.constrain_constant(num_bits.cell(), F::from(n as u64))
+            },
+        )
+    }
 
+    /// Returns the number of bits of `value`, `0` for zero. Only the low 128 bits are read;
+    /// a wider value is not in the table anyway.
+    fn bit_length(value: &Assigned<F>) -> u64 {
+        (128 - value.evaluate().get_lower_128().leading_zeros()) as u64
+    }
 }
 
-// Now let's test it! Here we define a circuit with a single value. and in syntesize function we assign that value
 #[cfg(test)]
 mod tests {
-    use core::num;
-
     use halo2_proofs::{
         circuit::floor_planner::V1,
         dev::{FailureLocation, MockProver, VerifyFailure},
         pasta::Fp,
-        plonk::{Any, Circuit},
+        plonk::Circuit,
     };
 
     use super::*;
 
+    const RANGE: usize = 8; // 3-bit value for the expression
+    const LOOKUP_NUMBITS: usize = 8;
+    const LOOKUP_RANGE: usize = 256; // 8-bit value table
+
+    /// Range checks `value` against the claimed `range` and asserts its bit length is
+    /// `expected_bits`.
     #[derive(Default)]
-    struct MyCircuit<F: FieldExt, const RANGE: usize, const LOOKUP_NUMBITS: usize, const LOOKUP_RANGE: usize> {
+    struct MyCircuit<F: FieldExt> {
         value: Value<Assigned<F>>,
-        large_value_num_bits: Option<usize>,
-        large_value: Value<Assigned<F>>
+        range: usize,
+        expected_bits: usize,
     }
 
-    impl<F: FieldExt, const RANGE: usize, const LOOKUP_NUMBITS: usize, const LOOKUP_RANGE: usize> Circuit<F> for MyCircuit<F, RANGE, LOOKUP_NUMBITS, LOOKUP_RANGE> {
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
         type Config = RangeCheckConfig<F, RANGE, LOOKUP_NUMBITS, LOOKUP_RANGE>;
         type FloorPlanner = V1;
 
         fn without_witnesses(&self) -> Self {
-            Self::default()
+            Self {
+                value: Value::unknown(),
+                ..*self
+            }
         }
 
         fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -210,82 +245,143 @@ mod tests {
             config: Self::Config,
             mut layouter: impl Layouter<F>,
         ) -> Result<(), Error> {
-            config.assign(layouter.namespace(|| "Assign value"), self.value, 0, RANGE)?;
-            config.assign(layouter.namespace(|| "Assign value"), self.value, self.large_value_num_bits.unwrap(), LOOKUP_RANGE)?;
-            // We need to load the values inside the lookup table! 
+            // We need to load the values inside the lookup table!
             config.table.load(&mut layouter)?;
-            Ok(())
+
+            let value = config.assign(
+                layouter.namespace(|| "Assign value"),
+                self.value,
+                self.range,
+            )?;
+            config.assert_bit_length(
+                layouter.namespace(|| "Assert bit length"),
+                value.num_bits(),
+                self.expected_bits,
+            )
         }
     }
 
-    #[test]
-    fn test_range_check_3() {
-        // our lookup table is 256 rows + last few rows or the advise colums 
-        // are automatically allocated to random values which are bliding factors
-        // so we need to use k=9
-        let k = 9;
-        const RANGE: usize = 8; // 3-bit value table
-        const LOOKUP_NUMBITS: usize = 8; // 8-bit value table 
-        const LOOKUP_RANGE: usize = 256; // 8-bit value table
-
-        let circuit = MyCircuit::<Fp, RANGE, LOOKUP_NUMBITS, LOOKUP_RANGE> {
-            value: Value::known(Fp::one().into()),
-            large_value_num_bits: Some(4), // 8 which is 4 bits
-            large_value: Value::known(Fp::from(8 as u64).into())
+    fn run(value: u64, range: usize, expected_bits: usize) -> MockProver<Fp> {
+        let circuit = MyCircuit::<Fp> {
+            value: Value::known(Fp::from(value).into()),
+            range,
+            expected_bits,
         };
+        // our lookup table is 256 rows plus the blinding rows, so we need to use k=9
+        MockProver::run(9, &circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn test_range_check_6() {
+        // The chip derives the bit length of every value in the table
+        run(0, LOOKUP_RANGE, 0).assert_satisfied();
+        for num_bits in 1..=LOOKUP_NUMBITS {
+            for value in (1 << (num_bits - 1))..(1 << num_bits) {
+                run(value, LOOKUP_RANGE, num_bits).assert_satisfied();
+            }
+        }
+
+        // Small ranges are checked by the expression as well
+        for value in 0..RANGE as u64 {
+            let num_bits = (64 - value.leading_zeros()) as usize;
+            run(value, RANGE, num_bits).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_range_check_6_wrong_bit_length() {
+        // 8 has 4 bits, not 3
+        let failures = run(8, LOOKUP_RANGE, 3).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|f| matches!(f, VerifyFailure::Permutation { .. })));
+    }
+
+    #[test]
+    fn test_range_check_6_out_of_range() {
+        // Out-of-range `value = 256` is not in the table
+        assert_eq!(
+            run(LOOKUP_RANGE as u64, LOOKUP_RANGE, 9).verify(),
+            Err(vec![VerifyFailure::Lookup {
+                lookup_index: 0,
+                location: FailureLocation::InRegion {
+                    region: (1, "Assign value").into(),
+                    offset: 0
+                }
+            }])
+        );
+
+        // `value = 8` is in the table, but not below RANGE
+        let failures = run(RANGE as u64, RANGE, 4).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|f| matches!(f, VerifyFailure::ConstraintNotSatisfied { .. })));
+    }
+
+    /// Assigns `(value, num_bits)` pairs directly, bypassing the bit length computed by
+    /// `assign`.
+    #[derive(Default)]
+    struct ForgedCircuit<F: FieldExt> {
+        value: Value<F>,
+        num_bits: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for ForgedCircuit<F> {
+        type Config = RangeCheckConfig<F, RANGE, LOOKUP_NUMBITS, LOOKUP_RANGE>;
+        type FloorPlanner = V1;
 
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
-        prover.assert_satisfied();
-
-        // // Successful cases large_value=0,1,2,3,4,5,6,7 (these should also pass the lookup range check)
-        // for i in 0..RANGE {
-        //     let circuit = MyCircuit::<Fp, RANGE, LOOKUP_NUMBITS, LOOKUP_RANGE> {
-        //         value: Value::known(Fp::from(i as u64).into()),
-        //         large_value : Value::known(Fp::from(i as u64).into())
-        //     };
-
-        //     let prover = MockProver::run(k, &circuit, vec![]).unwrap();
-        //     prover.assert_satisfied(); 
-        // }
-
-        // // Out-of-range `value = 8`
-        // {
-        //     let circuit = MyCircuit::<Fp, RANGE> {
-        //         value: Value::known(Fp::from(RANGE as u64).into()),
-        //     };
-        //     let prover = MockProver::run(k, &circuit, vec![]).unwrap();
-        //     // prover.assert_satisfied(); // this should fail!
-        //     assert_eq!(
-        //         prover.verify(),
-        //         Err(vec![VerifyFailure::ConstraintNotSatisfied {
-        //             constraint: ((0, "range check").into(), 0, "range check").into(),
-        //             location: FailureLocation::InRegion {
-        //                 region: (0, "Assign value").into(),
-        //                 offset: 0
-        //             },
-        //             cell_values: vec![(((Any::Advice, 0).into(), 0).into(), "0x8".to_string())]
-        //         }])
-        //     );
-        // }
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let num_bits = meta.advice_column();
+            RangeCheckConfig::configure(meta, value, num_bits)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.table.load(&mut layouter)?;
+
+            layouter.assign_region(
+                || "Forged bit length",
+                |mut region| {
+                    config.q_lookup.enable(&mut region, 0)?;
+                    region.assign_advice(|| "num_bits", config.num_bits, 0, || self.num_bits)?;
+                    region.assign_advice(|| "value", config.value, 0, || self.value)?;
+                    Ok(())
+                },
+            )
+        }
     }
 
-    // #[cfg(feature = "dev-graph")]
-    // #[test]
-    // fn print_range_check_2() {
-    //     use plotters::prelude::*;
-
-    //     let root = BitMapBackend::new("range-check-2-layout.png", (1024, 3096)).into_drawing_area();
-    //     root.fill(&WHITE).unwrap();
-    //     let root = root
-    //         .titled("Range Check 2 Layout", ("sans-serif", 60))
-    //         .unwrap();
-
-    //     let circuit = MyCircuit::<Fp, 8, 256> {
-    //         value: Value::unknown(),
-    //         large_value: Value::unknown()
-    //     };
-    //     halo2_proofs::dev::CircuitLayout::default()
-    //         .render(3, &circuit, &root)
-    //         .unwrap();
-    // }
-} 
\ No newline at end of file
+    #[test]
+    fn test_range_check_6_forged_bit_length() {
+        for (value, num_bits, honest) in [(8, 4, true), (8, 3, false), (0, 0, true), (0, 1, false)]
+        {
+            let circuit = ForgedCircuit::<Fp> {
+                value: Value::known(Fp::from(value)),
+                num_bits: Value::known(Fp::from(num_bits)),
+            };
+            let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+            if honest {
+                prover.assert_satisfied();
+            } else {
+                assert_eq!(
+                    prover.verify(),
+                    Err(vec![VerifyFailure::Lookup {
+                        lookup_index: 0,
+                        location: FailureLocation::InRegion {
+                            region: (1, "Forged bit length").into(),
+                            offset: 0
+                        }
+                    }])
+                );
+            }
+        }
+    }
+}
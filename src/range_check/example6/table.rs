@@ -1,58 +1,51 @@
-// here we are gonna define our lookup table
-// A lookup table of values up to RANGE 
-// e.g. RANGE = 256, values = [0..255]
-// The table is tagged by an index k where k is the number of bits of the element in the `value` column.
-// Once this is create it can be used inside our main config!  
-
 use std::marker::PhantomData;
 
-use halo2_proofs::{plonk::{Error, TableColumn, ConstraintSystem}, arithmetic::FieldExt, circuit::{Value, Layouter}, dev::metadata::Constraint};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error, TableColumn},
+};
 
-// This is a table with a NOW 2 columns. 
-// TableColumn is a Fixed Column
+/// A lookup table of values up to RANGE
+/// e.g. RANGE = 256, values = [0..255]
+/// This table is tagged by an index `k`, where `k` is the number of bits of the element in the `value` column.
+/// Its first row is the default `(0, 0)`, which disabled lookups resolve to; it is also the
+/// only row for `0`, so every value has exactly one tag and `0` is a 0-bit value.
 #[derive(Debug, Clone)]
-pub(super) struct RangeCheckTable<F:FieldExt, const NUM_BITS: usize, const RANGE: usize> {
-    pub(super) num_bits: TableColumn,
-    pub(super) value: TableColumn,
-    _marker: PhantomData<F>
+pub struct RangeCheckTable<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> {
+    pub num_bits: TableColumn,
+    pub value: TableColumn,
+    _marker: PhantomData<F>,
 }
 
-impl<F:FieldExt, const NUM_BITS: usize, const RANGE: usize> RangeCheckTable<F, NUM_BITS, RANGE> {
-
-
-    // create a configure function to allow to configure the table in the first place
-    pub(super) fn configure(
-        meta: &mut ConstraintSystem<F>
-    ) -> Self {
+impl<F: FieldExt, const NUM_BITS: usize, const RANGE: usize> RangeCheckTable<F, NUM_BITS, RANGE> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
         // check that 2^NUM_BITS = RANGE
         assert_eq!(1 << NUM_BITS, RANGE);
 
-        // API to create these special fixed columns, which are look_up columns
         let value = meta.lookup_table_column();
         let num_bits = meta.lookup_table_column();
+
         Self {
             num_bits,
             value,
-            _marker: PhantomData
+            _marker: PhantomData,
         }
     }
 
-
-    // load function assign the values to our fixed table
-    // This action is performed at key gen time
-    pub(super) fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         layouter.assign_table(
             || "load range-check table",
             |mut table| {
                 let mut offset = 0;
 
-                // Assign (num_bits = 1, value = 0)
+                // Assign the default row (num_bits = 0, value = 0)
                 {
                     table.assign_cell(
                         || "assign num_bits",
                         self.num_bits,
                         offset,
-                        || Value::known(F::one()),
+                        || Value::known(F::zero()),
                     )?;
                     table.assign_cell(
                         || "assign value",
@@ -80,12 +73,10 @@ impl<F:FieldExt, const NUM_BITS: usize, const RANGE: usize> RangeCheckTable<F, N
                         )?;
                         offset += 1;
                     }
-
                 }
 
                 Ok(())
             },
         )
-
     }
-}
\ No newline at end of file
+}
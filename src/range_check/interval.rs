@@ -0,0 +1,307 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::decompose::{DecomposeChip, DecomposeConfig};
+
+/// A bound of an interval, either fixed in the circuit or read from a cell.
+#[derive(Debug, Clone)]
+pub enum Bound<F: FieldExt> {
+    Constant(F),
+    Cell(AssignedCell<F, F>),
+}
+
+/// This chip checks that `lo <= v < hi` for arbitrary bounds, by checking that both
+/// `v - lo` and `hi - 1 - v` fit in `num_bits` bits with `DecomposeChip`.
+///
+/// The two differences add up to `hi - 1 - lo`, so as long as `hi - lo <= 2^num_bits` they
+/// cannot both fit unless `v` lies in the interval. With constant bounds this is checked
+/// when the circuit is built; with a bound in a cell, `hi - 1 - lo` is range checked too,
+/// which also rules out an empty interval.
+///
+///       a       |     b       |     c        |  q_interval
+///     ----------------------------------------------------
+///       v       |     lo      |     hi       |      1
+///     v - lo    |  hi - 1 - v |  hi - 1 - lo |      0
+///
+#[derive(Debug, Clone)]
+pub struct IntervalConfig<F: FieldExt, const RANGE: usize> {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    c: Column<Advice>,
+    q_interval: Selector,
+    pub decompose: DecomposeConfig<F, RANGE>,
+}
+
+pub struct IntervalChip<F: FieldExt, const RANGE: usize> {
+    config: IntervalConfig<F, RANGE>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const RANGE: usize> Chip<F> for IntervalChip<F, RANGE> {
+    type Config = IntervalConfig<F, RANGE>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const RANGE: usize> IntervalChip<F, RANGE> {
+    pub fn construct(config: IntervalConfig<F, RANGE>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Constant bounds are assigned through the constants column enabled by `decompose`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: Column<Advice>,
+        b: Column<Advice>,
+        c: Column<Advice>,
+        decompose: DecomposeConfig<F, RANGE>,
+    ) -> IntervalConfig<F, RANGE> {
+        let q_interval = meta.selector();
+
+        for column in [a, b, c] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("interval", |meta| {
+            let q = meta.query_selector(q_interval);
+            let v = meta.query_advice(a, Rotation::cur());
+            let lo = meta.query_advice(b, Rotation::cur());
+            let hi = meta.query_advice(c, Rotation::cur());
+            let below = meta.query_advice(a, Rotation::next());
+            let above = meta.query_advice(b, Rotation::next());
+            let width = meta.query_advice(c, Rotation::next());
+
+            let hi_minus_one = hi - Expression::Constant(F::one());
+
+            Constraints::with_selector(
+                q,
+                [
+                    ("below = v - lo", below - (v.clone() - lo.clone())),
+                    ("above = hi - 1 - v", above - (hi_minus_one.clone() - v)),
+                    ("width = hi - 1 - lo", width - (hi_minus_one - lo)),
+                ],
+            )
+        });
+
+        IntervalConfig {
+            a,
+            b,
+            c,
+            q_interval,
+            decompose,
+        }
+    }
+
+    /// Constrains `value` to `lo..hi`, where `hi - lo <= 2^num_bits`.
+    pub fn check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        lo: &Bound<F>,
+        hi: &Bound<F>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        let config = &self.config;
+
+        if let (Bound::Constant(lo), Bound::Constant(hi)) = (lo, hi) {
+            let width = *hi - F::one() - lo;
+            let fits = F::from_u128(width.get_lower_128()) == width
+                && (num_bits >= 128 || width.get_lower_128() >> num_bits == 0);
+            assert!(fits, "interval is empty or wider than 2^{}", num_bits);
+        }
+
+        let (below, above, width) = layouter.assign_region(
+            || "interval",
+            |mut region| {
+                config.q_interval.enable(&mut region, 0)?;
+
+                let v = value.copy_advice(|| "v", &mut region, config.a, 0)?;
+                let lo = Self::assign_bound(&mut region, lo, "lo", config.b)?;
+                let hi = Self::assign_bound(&mut region, hi, "hi", config.c)?;
+
+                let below = v.value().copied() - lo.value();
+                let above = hi.value().zip(v.value()).map(|(hi, v)| *hi - F::one() - v);
+                let width = hi
+                    .value()
+                    .zip(lo.value())
+                    .map(|(hi, lo)| *hi - F::one() - lo);
+
+                Ok((
+                    region.assign_advice(|| "v - lo", config.a, 1, || below)?,
+                    region.assign_advice(|| "hi - 1 - v", config.b, 1, || above)?,
+                    region.assign_advice(|| "hi - 1 - lo", config.c, 1, || width)?,
+                ))
+            },
+        )?;
+
+        let decompose = DecomposeChip::construct(config.decompose.clone());
+        decompose.copy_check(layouter.namespace(|| "v - lo"), &below, num_bits)?;
+        decompose.copy_check(layouter.namespace(|| "hi - 1 - v"), &above, num_bits)?;
+        if matches!(lo, Bound::Cell(_)) || matches!(hi, Bound::Cell(_)) {
+            decompose.copy_check(layouter.namespace(|| "hi - 1 - lo"), &width, num_bits)?;
+        }
+
+        Ok(())
+    }
+
+    fn assign_bound(
+        region: &mut Region<'_, F>,
+        bound: &Bound<F>,
+        name: &'static str,
+        column: Column<Advice>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        match bound {
+            Bound::Constant(constant) => {
+                region.assign_advice_from_constant(|| name, column, 0, *constant)
+            }
+            Bound::Cell(cell) => cell.copy_advice(|| name, region, column, 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range_check::example2::RangeTableConfig;
+    use halo2_proofs::{
+        circuit::{floor_planner::V1, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::Circuit,
+    };
+
+    const RANGE: usize = 256;
+
+    #[derive(Debug, Clone)]
+    struct TestConfig<F: FieldExt> {
+        input: Column<Advice>,
+        interval: IntervalConfig<F, RANGE>,
+    }
+
+    /// Checks `lo <= value < hi`, with the bounds as constants or, with `cell_bounds`, as
+    /// witnessed cells.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        value: Value<F>,
+        lo: u64,
+        hi: u64,
+        num_bits: usize,
+        cell_bounds: bool,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = TestConfig<F>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                ..*self
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            meta.enable_equality(input);
+
+            let [a, b, c, z] = [(); 4].map(|_| meta.advice_column());
+            let table = RangeTableConfig::configure(meta);
+            let decompose = DecomposeChip::configure(meta, z, table);
+            let interval = IntervalChip::configure(meta, a, b, c, decompose);
+
+            TestConfig { input, interval }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.interval.decompose.table.load(&mut layouter)?;
+            let chip = IntervalChip::construct(config.interval);
+
+            let (value, lo, hi) = layouter.assign_region(
+                || "inputs",
+                |mut region| {
+                    let value = region.assign_advice(|| "value", config.input, 0, || self.value)?;
+                    let (lo, hi) = (F::from(self.lo), F::from(self.hi));
+                    if self.cell_bounds {
+                        let lo =
+                            region.assign_advice(|| "lo", config.input, 1, || Value::known(lo))?;
+                        let hi =
+                            region.assign_advice(|| "hi", config.input, 2, || Value::known(hi))?;
+                        Ok((value, Bound::Cell(lo), Bound::Cell(hi)))
+                    } else {
+                        Ok((value, Bound::Constant(lo), Bound::Constant(hi)))
+                    }
+                },
+            )?;
+
+            chip.check(
+                layouter.namespace(|| "interval"),
+                &value,
+                &lo,
+                &hi,
+                self.num_bits,
+            )
+        }
+    }
+
+    fn run(value: u64, lo: u64, hi: u64, num_bits: usize, cell_bounds: bool) -> MockProver<Fp> {
+        let circuit = MyCircuit {
+            value: Value::known(Fp::from(value)),
+            lo,
+            hi,
+            num_bits,
+            cell_bounds,
+        };
+        MockProver::run(9, &circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn test_interval_edges() {
+        for cell_bounds in [false, true] {
+            // Non-power-of-two bounds, with hi - lo = 990 <= 2^10.
+            for (lo, hi, num_bits) in [(10, 1000, 10), (0, 1, 1), (1 << 20, (1 << 20) + 3, 2)] {
+                for value in [lo, lo + (hi - lo) / 2, hi - 1] {
+                    run(value, lo, hi, num_bits, cell_bounds).assert_satisfied();
+                }
+                for value in [lo.wrapping_sub(1), hi] {
+                    assert!(run(value, lo, hi, num_bits, cell_bounds).verify().is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_interval_cell_bounds_rejects_empty() {
+        // With hi <= lo, no value satisfies both checks and the width check fails too.
+        assert!(run(5, 5, 5, 8, true).verify().is_err());
+        assert!(run(5, 6, 5, 8, true).verify().is_err());
+
+        // hi - lo is wider than 2^num_bits.
+        assert!(run(5, 0, 300, 8, true).verify().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "wider than")]
+    fn test_interval_constant_bounds_too_wide() {
+        run(5, 0, 300, 8, false);
+    }
+}
@@ -0,0 +1,255 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use super::decompose::DecomposeChip;
+use super::example2::RangeTableConfig;
+use super::interval::{Bound, IntervalChip, IntervalConfig};
+use crate::util::min_k;
+
+/// Index of the instance row holding the limit.
+pub const LIMIT_ROW: usize = 0;
+
+/// Size of the range table behind the checks.
+const TABLE_RANGE: usize = 256;
+
+/// This chip checks that a private value lies below a limit read from the instance column,
+/// so the limit is not baked into the verifying key.
+///
+/// The limit is copied from the instance into `input`, and `IntervalChip` checks
+/// `0 <= v < limit` with the limit as a cell bound. That also checks `limit - 1` against
+/// `num_bits` bits, so any limit in `1..=2^num_bits` is accepted.
+#[derive(Debug, Clone)]
+pub struct PublicBoundConfig<F: FieldExt, const RANGE: usize> {
+    input: Column<Advice>,
+    instance: Column<Instance>,
+    pub interval: IntervalConfig<F, RANGE>,
+}
+
+pub struct PublicBoundChip<F: FieldExt, const RANGE: usize> {
+    config: PublicBoundConfig<F, RANGE>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const RANGE: usize> Chip<F> for PublicBoundChip<F, RANGE> {
+    type Config = PublicBoundConfig<F, RANGE>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const RANGE: usize> PublicBoundChip<F, RANGE> {
+    pub fn construct(config: PublicBoundConfig<F, RANGE>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        interval: IntervalConfig<F, RANGE>,
+    ) -> PublicBoundConfig<F, RANGE> {
+        meta.enable_equality(input);
+        meta.enable_equality(instance);
+
+        PublicBoundConfig {
+            input,
+            instance,
+            interval,
+        }
+    }
+
+    /// Witnesses a private value in `input`.
+    pub fn assign_value(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "value",
+            |mut region| region.assign_advice(|| "value", self.config.input, 0, || value),
+        )
+    }
+
+    /// Constrains `value` to be below the limit in instance row `row`.
+    pub fn check_below(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        row: usize,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        let limit = layouter.assign_region(
+            || "limit",
+            |mut region| {
+                region.assign_advice_from_instance(
+                    || "limit",
+                    self.config.instance,
+                    row,
+                    self.config.input,
+                    0,
+                )
+            },
+        )?;
+
+        IntervalChip::construct(self.config.interval.clone()).check(
+            layouter.namespace(|| "0 <= value < limit"),
+            value,
+            &Bound::Constant(F::zero()),
+            &Bound::Cell(limit),
+            num_bits,
+        )
+    }
+}
+
+/// Proves that a private `NUM_BITS`-bit value lies below the public limit in instance row
+/// `LIMIT_ROW`, for any limit in `1..=2^NUM_BITS`.
+#[derive(Default)]
+pub struct MyCircuit<F, const NUM_BITS: usize> {
+    value: Value<F>,
+}
+
+impl<F: FieldExt, const NUM_BITS: usize> MyCircuit<F, NUM_BITS> {
+    pub fn new(value: u64) -> Self {
+        Self {
+            value: Value::known(F::from(value)),
+        }
+    }
+
+    /// Returns the smallest `k` that fits the circuit.
+    pub fn k() -> u32 {
+        // The range table needs a row per value; the checks fit beside it.
+        min_k::<F, Self>(TABLE_RANGE)
+    }
+}
+
+impl<F: FieldExt, const NUM_BITS: usize> Circuit<F> for MyCircuit<F, NUM_BITS> {
+    type Config = PublicBoundConfig<F, TABLE_RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let input = meta.advice_column();
+        let instance = meta.instance_column();
+        let [a, b, c, z] = [(); 4].map(|_| meta.advice_column());
+
+        let table = RangeTableConfig::configure(meta);
+        let decompose = DecomposeChip::configure(meta, z, table);
+        let interval = IntervalChip::configure(meta, a, b, c, decompose);
+
+        PublicBoundChip::configure(meta, input, instance, interval)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.interval.decompose.table.load(&mut layouter)?;
+        let chip = PublicBoundChip::construct(config);
+
+        let value = chip.assign_value(layouter.namespace(|| "value"), self.value)?;
+        chip.check_below(
+            layouter.namespace(|| "below limit"),
+            &value,
+            LIMIT_ROW,
+            NUM_BITS,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MyCircuit;
+    use halo2_proofs::{
+        dev::MockProver,
+        pasta::{EqAffine, Fp},
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, SingleVerifier},
+        poly::commitment::Params,
+        transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+    };
+    use rand_core::OsRng;
+
+    const NUM_BITS: usize = 8;
+    type PublicBoundCircuit = MyCircuit<Fp, NUM_BITS>;
+
+    fn run(value: u64, limit: u64) -> MockProver<Fp> {
+        let circuit = PublicBoundCircuit::new(value);
+        MockProver::run(
+            PublicBoundCircuit::k(),
+            &circuit,
+            vec![vec![Fp::from(limit)]],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn public_bound() {
+        assert_eq!(PublicBoundCircuit::k(), 9);
+
+        for (value, limit) in [(0, 1), (5, 6), (5, 10), (0, 256), (255, 256)] {
+            run(value, limit).assert_satisfied();
+        }
+
+        // The value must be strictly below the limit.
+        for (value, limit) in [(6, 6), (7, 6), (0, 0), (256, 256)] {
+            assert!(run(value, limit).verify().is_err());
+        }
+
+        // Limits above 2^NUM_BITS are rejected.
+        assert!(run(5, 257).verify().is_err());
+    }
+
+    #[test]
+    fn public_bound_one_vk() {
+        let k = PublicBoundCircuit::k();
+        let params: Params<EqAffine> = Params::new(k);
+
+        // Keys are generated once, without knowing the limit.
+        let empty = PublicBoundCircuit::default();
+        let vk = keygen_vk(&params, &empty).unwrap();
+        let pk = keygen_pk(&params, vk, &empty).unwrap();
+
+        for limit in [10, 200] {
+            let public_input = vec![Fp::from(limit)];
+
+            let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(vec![]);
+            create_proof(
+                &params,
+                &pk,
+                &[PublicBoundCircuit::new(7)],
+                &[&[&public_input]],
+                &mut OsRng,
+                &mut transcript,
+            )
+            .unwrap();
+            let proof = transcript.finalize();
+
+            let mut transcript = Blake2bRead::<_, EqAffine, Challenge255<_>>::init(&proof[..]);
+            verify_proof(
+                &params,
+                pk.get_vk(),
+                SingleVerifier::new(&params),
+                &[&[&public_input]],
+                &mut transcript,
+            )
+            .unwrap();
+        }
+    }
+}
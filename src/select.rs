@@ -0,0 +1,239 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+/// This chip returns `cond ? x : y`, with `cond` constrained to be boolean.
+///
+///       cond  |  x  |  y  |  out  |  selector
+///     --------------------------------------------
+///        c    |  x  |  y  |  out  |     1
+///
+/// `out = y + cond * (x - y)`, so the condition can be any boolean cell, such as the output
+/// of `IsEqualChip`.
+#[derive(Debug, Clone)]
+pub struct SelectConfig {
+    cond: Column<Advice>,
+    x: Column<Advice>,
+    y: Column<Advice>,
+    out: Column<Advice>,
+    selector: Selector,
+}
+
+pub struct SelectChip<F: FieldExt> {
+    config: SelectConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for SelectChip<F> {
+    type Config = SelectConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> SelectChip<F> {
+    pub fn construct(config: SelectConfig) -> Self {
+        SelectChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        cond: Column<Advice>,
+        x: Column<Advice>,
+        y: Column<Advice>,
+        out: Column<Advice>,
+    ) -> SelectConfig {
+        let selector = meta.selector();
+
+        for column in [cond, x, y, out] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("select", |meta| {
+            let s = meta.query_selector(selector);
+            let cond = meta.query_advice(cond, Rotation::cur());
+            let x = meta.query_advice(x, Rotation::cur());
+            let y = meta.query_advice(y, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            Constraints::with_selector(
+                s,
+                [
+                    (
+                        "cond is boolean",
+                        cond.clone() * (Expression::Constant(F::one()) - cond.clone()),
+                    ),
+                    ("out = cond ? x : y", out - (y.clone() + cond * (x - y))),
+                ],
+            )
+        });
+
+        SelectConfig {
+            cond,
+            x,
+            y,
+            out,
+            selector,
+        }
+    }
+
+    /// Returns a cell holding `x` if `cond` is 1 and `y` if it is 0.
+    pub fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: &AssignedCell<F, F>,
+        x: &AssignedCell<F, F>,
+        y: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                cond.copy_advice(|| "cond", &mut region, self.config.cond, 0)?;
+                x.copy_advice(|| "x", &mut region, self.config.x, 0)?;
+                y.copy_advice(|| "y", &mut region, self.config.y, 0)?;
+
+                let out = cond
+                    .value()
+                    .zip(x.value())
+                    .zip(y.value())
+                    .map(|((cond, x), y)| *y + *cond * (*x - y));
+                region.assign_advice(|| "out", self.config.out, 0, || out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::is_equal::{IsEqualChip, IsEqualConfig};
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    #[derive(Debug, Clone)]
+    struct TestConfig<F: FieldExt> {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        is_equal: IsEqualConfig<F>,
+        select: SelectConfig,
+    }
+
+    /// Exposes `if a == b { c } else { d }`, the shape of the function in
+    /// `fibonacci/example3.rs`, without a bespoke gate. `cond` overrides the comparison.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        inputs: [F; 4],
+        cond: Option<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = TestConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+
+            let [a, b, c, d] = [(); 4].map(|_| meta.advice_column());
+            let is_equal = IsEqualChip::configure(meta, a, b, c, d);
+            let select = SelectChip::configure(meta, a, b, c, d);
+
+            TestConfig {
+                input,
+                instance,
+                is_equal,
+                select,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let is_equal = IsEqualChip::construct(config.is_equal);
+            let select = SelectChip::construct(config.select);
+
+            let (inputs, cond) = layouter.assign_region(
+                || "inputs",
+                |mut region| {
+                    let inputs = self
+                        .inputs
+                        .iter()
+                        .enumerate()
+                        .map(|(row, value)| {
+                            let value = Value::known(*value);
+                            region.assign_advice(|| "input", config.input, row, || value)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let cond = self
+                        .cond
+                        .map(|cond| {
+                            region.assign_advice(|| "cond", config.input, 4, || Value::known(cond))
+                        })
+                        .transpose()?;
+                    Ok((inputs, cond))
+                },
+            )?;
+
+            let cond = match cond {
+                Some(cond) => cond,
+                None => is_equal.assign(layouter.namespace(|| "a == b"), &inputs[0], &inputs[1])?,
+            };
+            let out = select.select(layouter.namespace(|| "select"), &cond, &inputs[2], &inputs[3])?;
+            layouter.constrain_instance(out.cell(), config.instance, 0)
+        }
+    }
+
+    #[test]
+    fn test_select_chip() {
+        let k = 4;
+        let [a, b, c, d] = [3, 4, 10, 20].map(Fp::from);
+
+        for (inputs, out) in [([a, a, c, d], c), ([a, b, c, d], d)] {
+            let circuit = MyCircuit { inputs, cond: None };
+            let prover = MockProver::run(k, &circuit, vec![vec![out]]).unwrap();
+            prover.assert_satisfied();
+
+            let wrong = if out == c { d } else { c };
+            let prover = MockProver::run(k, &circuit, vec![vec![wrong]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        // A non-boolean condition would blend the inputs; the boolean constraint rejects it.
+        let two = Fp::from(2);
+        let circuit = MyCircuit {
+            inputs: [a, b, c, d],
+            cond: Some(two),
+        };
+        let blended = d + two * (c - d);
+        let prover = MockProver::run(k, &circuit, vec![vec![blended]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
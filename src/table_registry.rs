@@ -0,0 +1,341 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error, TableColumn},
+};
+
+use crate::range_check::example2::RangeTableConfig;
+
+/// The lookup tables a `TableRegistry` hands out, each with its size in its own unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TableKind {
+    /// One column holding `0..rows`.
+    Range { rows: usize },
+    /// Three columns holding `(lhs, rhs, lhs ^ rhs)` for every pair of `bits`-bit operands,
+    /// so `2^(2 * bits)` rows.
+    Xor { bits: usize },
+}
+
+impl TableKind {
+    fn num_columns(self) -> usize {
+        match self {
+            TableKind::Range { .. } => 1,
+            TableKind::Xor { .. } => 3,
+        }
+    }
+
+    fn load<F: FieldExt>(
+        self,
+        layouter: &mut impl Layouter<F>,
+        columns: &[TableColumn],
+    ) -> Result<(), Error> {
+        match self {
+            TableKind::Range { rows } => layouter.assign_table(
+                || format!("load range-check table 0..{}", rows),
+                |mut table| {
+                    for value in 0..rows {
+                        table.assign_cell(
+                            || "value",
+                            columns[0],
+                            value,
+                            || Value::known(F::from(value as u64)),
+                        )?;
+                    }
+                    Ok(())
+                },
+            ),
+            TableKind::Xor { bits } => layouter.assign_table(
+                || format!("load {} bit xor table", bits),
+                |mut table| {
+                    let mut offset = 0;
+                    for lhs in 0..1u64 << bits {
+                        for rhs in 0..1u64 << bits {
+                            for (column, value) in columns.iter().zip([lhs, rhs, lhs ^ rhs]) {
+                                table.assign_cell(
+                                    || "xor",
+                                    *column,
+                                    offset,
+                                    || Value::known(F::from(value)),
+                                )?;
+                            }
+                            offset += 1;
+                        }
+                    }
+                    Ok(())
+                },
+            ),
+        }
+    }
+}
+
+/// Hands out lookup tables keyed by `TableKind`, so that chips composed in one circuit
+/// share a single copy of each table instead of allocating and loading their own.
+///
+/// Chips request their tables from the registry while configuring. The registry then goes
+/// into the circuit config, and each synthesis loads the tables through a `TableLoader`,
+/// which skips tables already loaded and whose `finish` catches tables never loaded.
+#[derive(Debug, Clone, Default)]
+pub struct TableRegistry {
+    tables: BTreeMap<TableKind, Vec<TableColumn>>,
+}
+
+impl TableRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the columns of the `kind` table, allocating them on first use. Tables of the
+    /// same kind but a different size are distinct.
+    pub fn columns<F: FieldExt>(
+        &mut self,
+        meta: &mut ConstraintSystem<F>,
+        kind: TableKind,
+    ) -> &[TableColumn] {
+        self.tables.entry(kind).or_insert_with(|| {
+            (0..kind.num_columns())
+                .map(|_| meta.lookup_table_column())
+                .collect()
+        })
+    }
+
+    /// Returns the shared table of `0..RANGE`, that is `TableKind::Range { rows: RANGE }`.
+    pub fn range_table<F: FieldExt, const RANGE: usize>(
+        &mut self,
+        meta: &mut ConstraintSystem<F>,
+    ) -> RangeTableConfig<F, RANGE> {
+        RangeTableConfig::from_column(self.columns(meta, TableKind::Range { rows: RANGE })[0])
+    }
+
+    /// Returns the `lhs`, `rhs` and `lhs ^ rhs` columns of the shared table of `bits`-bit
+    /// xors, that is `TableKind::Xor { bits }`; `bits` is the operand width, not a row count.
+    pub fn xor_table<F: FieldExt>(
+        &mut self,
+        meta: &mut ConstraintSystem<F>,
+        bits: usize,
+    ) -> [TableColumn; 3] {
+        let columns = self.columns(meta, TableKind::Xor { bits });
+        [columns[0], columns[1], columns[2]]
+    }
+
+    /// Returns a loader for one synthesis pass. Floor planners such as `V1` synthesize
+    /// more than once, so the set of loaded tables is kept per loader, not in the registry.
+    pub fn loader(&self) -> TableLoader<'_> {
+        TableLoader {
+            registry: self,
+            loaded: BTreeSet::new(),
+        }
+    }
+}
+
+/// Loads the tables of a `TableRegistry` during one synthesis, each at most once.
+#[derive(Debug)]
+pub struct TableLoader<'a> {
+    registry: &'a TableRegistry,
+    loaded: BTreeSet<TableKind>,
+}
+
+impl TableLoader<'_> {
+    /// Loads the `kind` table unless it is already loaded. Fails with `Error::Synthesis` if
+    /// no chip requested the table while configuring.
+    pub fn load<F: FieldExt>(
+        &mut self,
+        layouter: &mut impl Layouter<F>,
+        kind: TableKind,
+    ) -> Result<(), Error> {
+        let columns = self.registry.tables.get(&kind).ok_or(Error::Synthesis)?;
+
+        if self.loaded.insert(kind) {
+            kind.load(layouter, columns)?;
+        }
+        Ok(())
+    }
+
+    /// Loads every table requested while configuring.
+    pub fn load_all<F: FieldExt>(&mut self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        for &kind in self.registry.tables.keys() {
+            self.load(layouter, kind)?;
+        }
+        Ok(())
+    }
+
+    /// Fails with `Error::Synthesis` if a table requested while configuring was never
+    /// loaded; its lookups would otherwise fail only when the proof is verified.
+    pub fn finish(self) -> Result<(), Error> {
+        if self
+            .registry
+            .tables
+            .keys()
+            .all(|key| self.loaded.contains(key))
+        {
+            Ok(())
+        } else {
+            Err(Error::Synthesis)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fibonacci::example4::{FibonacciChip, FibonacciConfig, LIMB_BITS};
+    use crate::fibonacci::{FibonacciInstructions, Seeds};
+    use crate::range_check::decompose::{DecomposeChip, DecomposeConfig};
+    use crate::range_check::example2::RangeCheckConfig;
+    use halo2_proofs::{
+        circuit::{floor_planner::V1, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Assigned, Circuit},
+    };
+
+    const RANGE: usize = 256;
+
+    #[derive(Debug, Clone)]
+    struct TestConfig<F: FieldExt> {
+        lookup: RangeCheckConfig<F, 8, RANGE>,
+        decompose: DecomposeConfig<F, RANGE>,
+        fibonacci: FibonacciConfig,
+        tables: TableRegistry,
+    }
+
+    /// Composes two range checks over the same 256-row table with the add/xor Fibonacci
+    /// chip, whose xor table has 256 rows too.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        value: Value<F>,
+        loads: &'static [TableKind],
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = TestConfig<F>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                loads: self.loads,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let mut tables = TableRegistry::new();
+
+            let value = meta.advice_column();
+            let table = tables.range_table(meta);
+            let lookup = RangeCheckConfig::configure_with_table(meta, value, table);
+
+            let z = meta.advice_column();
+            let table = tables.range_table(meta);
+            let decompose = DecomposeChip::configure(meta, z, table);
+
+            let fibonacci = FibonacciChip::configure_with_tables(meta, &mut tables);
+
+            TestConfig {
+                lookup,
+                decompose,
+                fibonacci,
+                tables,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let mut tables = config.tables.loader();
+            for &kind in self.loads {
+                tables.load(&mut layouter, kind)?;
+            }
+            tables.finish()?;
+
+            config.lookup.assign_lookup(
+                layouter.namespace(|| "lookup"),
+                self.value.map(Assigned::from),
+            )?;
+            DecomposeChip::construct(config.decompose).witness_check(
+                layouter.namespace(|| "decompose"),
+                self.value,
+                16,
+            )?;
+
+            let chip = FibonacciChip::construct(config.fibonacci);
+            let seeds = Seeds::Private(Value::known(F::one()), Value::known(F::one()));
+            chip.assign(layouter.namespace(|| "fibonacci"), &seeds, 5)?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_registry_shares_tables() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let mut tables = TableRegistry::new();
+
+        let a = tables.range_table::<Fp, RANGE>(&mut meta);
+        let b = tables.range_table::<Fp, RANGE>(&mut meta);
+        let c = tables.range_table::<Fp, 16>(&mut meta);
+        assert_eq!(a.value, b.value);
+        assert_ne!(a.value, c.value);
+
+        // Kinds are keyed separately, even when `rows` and `bits` are equal.
+        let xor = tables.xor_table(&mut meta, 16);
+        assert!(!xor.contains(&c.value));
+        assert_eq!(xor, tables.xor_table(&mut meta, 16));
+    }
+
+    /// Each chip asks for its own tables, so the range table is asked for twice.
+    const LOADS: &[TableKind] = &[
+        TableKind::Range { rows: RANGE },
+        TableKind::Range { rows: RANGE },
+        TableKind::Xor { bits: LIMB_BITS },
+    ];
+
+    fn run(value: u64, loads: &'static [TableKind]) -> Result<MockProver<Fp>, Error> {
+        let circuit = MyCircuit {
+            value: Value::known(Fp::from(value)),
+            loads,
+        };
+        MockProver::run(9, &circuit, vec![vec![]])
+    }
+
+    #[test]
+    fn test_registry_loads_once() {
+        run(200, LOADS).unwrap().assert_satisfied();
+        run(
+            200,
+            &[
+                TableKind::Xor { bits: LIMB_BITS },
+                TableKind::Range { rows: RANGE },
+            ],
+        )
+        .unwrap()
+        .assert_satisfied();
+
+        // Both range checks look up the shared table.
+        assert!(run(300, LOADS).unwrap().verify().is_err());
+    }
+
+    #[test]
+    fn test_registry_rejects_missing_tables() {
+        // The xor table is requested while configuring but never loaded.
+        assert!(matches!(
+            run(200, &[TableKind::Range { rows: RANGE }]),
+            Err(Error::Synthesis)
+        ));
+
+        // No chip requested a 16-row range table.
+        assert!(matches!(
+            run(
+                200,
+                &[
+                    TableKind::Range { rows: 16 },
+                    TableKind::Range { rows: RANGE }
+                ]
+            ),
+            Err(Error::Synthesis)
+        ));
+    }
+}
@@ -0,0 +1,19 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    plonk::{Circuit, ConstraintSystem},
+};
+
+/// Returns the smallest `k` such that circuit `C` has at least `rows` usable rows.
+pub fn min_k<F: FieldExt, C: Circuit<F>>(rows: usize) -> u32 {
+    let mut meta = ConstraintSystem::default();
+    C::configure(&mut meta);
+
+    // The last rows of the circuit are reserved for blinding factors.
+    let rows = std::cmp::max(rows + meta.blinding_factors() + 1, meta.minimum_rows());
+
+    let mut k = 1;
+    while (1 << k) < rows {
+        k += 1;
+    }
+    k
+}
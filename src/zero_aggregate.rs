@@ -0,0 +1,327 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+/// This chip returns whether all, or at least one, of a list of cells are zero, using three
+/// advice columns whatever the length of the list.
+///
+/// Both predicates start from `acc = 1` in the first row and fold one value per row.
+///
+/// `all_zero` keeps a per-row inverse so that `1 - value * inv` is the is-zero flag of the
+/// row, and multiplies the flags together:
+///
+///       value  |  inv  |  acc                        |  q_all
+///     ----------------------------------------------------------
+///              |       |  1                          |    0
+///        v_1   | 1/v_1 |  acc_prev * (1 - v_1 * inv) |    1
+///        ...   |  ...  |  ...                        |    1
+///
+/// `any_zero` multiplies the values themselves and needs a single inverse, of the final
+/// product, to turn it into a flag:
+///
+///       value  |  inv  |  acc                  |  q_any  |  q_any_out
+///     ----------------------------------------------------------------
+///              |       |  1                    |    0    |     0
+///        v_1   |       |  v_1                  |    1    |     0
+///        ...   |       |  ...                  |    1    |     0
+///        v_n   | 1/p   |  p = v_1 * ... * v_n  |    1    |     1
+///              |       |  1 - p * inv          |    0    |     0
+///
+/// An empty list is all-zero and not any-zero.
+#[derive(Debug, Clone)]
+pub struct ZeroAggregateConfig {
+    value: Column<Advice>,
+    inv: Column<Advice>,
+    acc: Column<Advice>,
+    q_all: Selector,
+    q_any: Selector,
+    q_any_out: Selector,
+}
+
+pub struct ZeroAggregateChip<F: FieldExt> {
+    config: ZeroAggregateConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for ZeroAggregateChip<F> {
+    type Config = ZeroAggregateConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> ZeroAggregateChip<F> {
+    pub fn construct(config: ZeroAggregateConfig) -> Self {
+        ZeroAggregateChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        inv: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> ZeroAggregateConfig {
+        let constants = meta.fixed_column();
+        let q_all = meta.selector();
+        let q_any = meta.selector();
+        let q_any_out = meta.selector();
+
+        meta.enable_equality(value);
+        meta.enable_equality(acc);
+        meta.enable_constant(constants);
+
+        meta.create_gate("all zero", |meta| {
+            let q = meta.query_selector(q_all);
+            let value = meta.query_advice(value, Rotation::cur());
+            let inv = meta.query_advice(inv, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let acc = meta.query_advice(acc, Rotation::cur());
+
+            let is_zero = Expression::Constant(F::one()) - value.clone() * inv;
+
+            Constraints::with_selector(
+                q,
+                [
+                    (
+                        "value is zero or inv is its inverse",
+                        value * is_zero.clone(),
+                    ),
+                    ("acc = acc_prev * is_zero", acc - acc_prev * is_zero),
+                ],
+            )
+        });
+
+        meta.create_gate("any zero product", |meta| {
+            let q = meta.query_selector(q_any);
+            let value = meta.query_advice(value, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let acc = meta.query_advice(acc, Rotation::cur());
+
+            Constraints::with_selector(q, [("acc = acc_prev * value", acc - acc_prev * value)])
+        });
+
+        meta.create_gate("any zero result", |meta| {
+            let q = meta.query_selector(q_any_out);
+            let product = meta.query_advice(acc, Rotation::cur());
+            let inv = meta.query_advice(inv, Rotation::cur());
+            let out = meta.query_advice(acc, Rotation::next());
+
+            Constraints::with_selector(
+                q,
+                [
+                    (
+                        "out = 1 - product * inv",
+                        out.clone() - (Expression::Constant(F::one()) - product.clone() * inv),
+                    ),
+                    ("product is zero or out is", product * out),
+                ],
+            )
+        });
+
+        ZeroAggregateConfig {
+            value,
+            inv,
+            acc,
+            q_all,
+            q_any,
+            q_any_out,
+        }
+    }
+
+    /// Returns a cell holding `1` if every cell in `cells` is zero and `0` otherwise.
+    pub fn all_zero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+
+        layouter.assign_region(
+            || "all zero",
+            |mut region| {
+                let mut acc =
+                    region.assign_advice_from_constant(|| "acc", config.acc, 0, F::one())?;
+
+                for (i, cell) in cells.iter().enumerate() {
+                    let row = i + 1;
+                    config.q_all.enable(&mut region, row)?;
+
+                    let value = cell.copy_advice(|| "value", &mut region, config.value, row)?;
+                    let inv = value.value().map(|v| v.invert().unwrap_or(F::zero()));
+                    region.assign_advice(|| "inv", config.inv, row, || inv)?;
+
+                    let is_zero = value.value().map(|v| F::from(*v == F::zero()));
+                    let next = acc.value().copied() * is_zero;
+                    acc = region.assign_advice(|| "acc", config.acc, row, || next)?;
+                }
+
+                Ok(acc)
+            },
+        )
+    }
+
+    /// Returns a cell holding `1` if at least one cell in `cells` is zero and `0` otherwise.
+    pub fn any_zero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+
+        layouter.assign_region(
+            || "any zero",
+            |mut region| {
+                let mut product =
+                    region.assign_advice_from_constant(|| "acc", config.acc, 0, F::one())?;
+
+                for (i, cell) in cells.iter().enumerate() {
+                    let row = i + 1;
+                    config.q_any.enable(&mut region, row)?;
+
+                    let value = cell.copy_advice(|| "value", &mut region, config.value, row)?;
+                    let next = product.value().copied() * value.value();
+                    product = region.assign_advice(|| "acc", config.acc, row, || next)?;
+                }
+
+                let row = cells.len();
+                config.q_any_out.enable(&mut region, row)?;
+
+                let inv = product.value().map(|p| p.invert().unwrap_or(F::zero()));
+                region.assign_advice(|| "inv", config.inv, row, || inv)?;
+
+                let out = product.value().map(|p| F::from(*p == F::zero()));
+                region.assign_advice(|| "out", config.acc, row + 1, || out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    #[derive(Debug, Clone)]
+    struct TestConfig {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        aggregate: ZeroAggregateConfig,
+    }
+
+    /// Exposes whether all, and whether any, of the values are zero.
+    #[derive(Default)]
+    struct MyCircuit<F> {
+        values: Vec<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+        type Config = TestConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: self.values.clone(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+
+            let [value, inv, acc] = [(); 3].map(|_| meta.advice_column());
+            let aggregate = ZeroAggregateChip::configure(meta, value, inv, acc);
+
+            TestConfig {
+                input,
+                instance,
+                aggregate,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = ZeroAggregateChip::construct(config.aggregate);
+
+            let cells = layouter.assign_region(
+                || "values",
+                |mut region| {
+                    self.values
+                        .iter()
+                        .enumerate()
+                        .map(|(row, value)| {
+                            region.assign_advice(
+                                || "value",
+                                config.input,
+                                row,
+                                || Value::known(*value),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+
+            let all = chip.all_zero(layouter.namespace(|| "all zero"), &cells)?;
+            let any = chip.any_zero(layouter.namespace(|| "any zero"), &cells)?;
+            layouter.constrain_instance(all.cell(), config.instance, 0)?;
+            layouter.constrain_instance(any.cell(), config.instance, 1)
+        }
+    }
+
+    #[test]
+    fn test_zero_aggregate() {
+        let k = 6;
+
+        for (values, all, any) in [
+            (vec![0, 0, 0], true, true),
+            (vec![0, 5, 0], false, true),
+            (vec![1, 2, 3, 4, 5, 6, 7, 8], false, false),
+            (vec![7], false, false),
+            (vec![0], true, true),
+            (vec![], true, false),
+        ] {
+            let circuit = MyCircuit {
+                values: values.into_iter().map(Fp::from).collect(),
+            };
+
+            let public_input = vec![Fp::from(all), Fp::from(any)];
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+
+            // Flipping either claim is caught by the copy to the instance.
+            for flipped in [
+                vec![Fp::from(!all), Fp::from(any)],
+                vec![Fp::from(all), Fp::from(!any)],
+            ] {
+                let prover = MockProver::run(k, &circuit, vec![flipped]).unwrap();
+                let failures = prover.verify().unwrap_err();
+                assert!(failures
+                    .iter()
+                    .all(|f| matches!(f, VerifyFailure::Permutation { .. })));
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::marker::PhantomData;
+
+use halo2_examples::gadgets::fibonacci::{
+    add_xor, function, single_column, three_column, FibonacciInstructions, Seeds, OUTPUT_ROW,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    pasta::Fp,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+/// Proves f(nterms - 1) with any Fibonacci layout; only the chip differs between the circuits
+/// below.
+fn synthesize_fibonacci<F: FieldExt>(
+    chip: &impl FibonacciInstructions<F>,
+    mut layouter: impl Layouter<F>,
+    nterms: usize,
+) -> Result<(), Error> {
+    chip.load(layouter.namespace(|| "lookup table"))?;
+    let out = chip.assign(
+        layouter.namespace(|| "entire table"),
+        &Seeds::Public,
+        nterms,
+    )?;
+    chip.expose_public(layouter.namespace(|| "out"), &out, OUTPUT_ROW)
+}
+
+struct ThreeColumnCircuit<F>(usize, PhantomData<F>);
+
+impl<F: FieldExt> Circuit<F> for ThreeColumnCircuit<F> {
+    type Config = three_column::FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self(self.0, PhantomData)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        three_column::FibonacciChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        synthesize_fibonacci(
+            &three_column::FibonacciChip::construct(config),
+            layouter,
+            self.0,
+        )
+    }
+}
+
+struct SingleColumnCircuit<F>(usize, PhantomData<F>);
+
+impl<F: FieldExt> Circuit<F> for SingleColumnCircuit<F> {
+    type Config = single_column::FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self(self.0, PhantomData)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        single_column::FibonacciChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        synthesize_fibonacci(
+            &single_column::FibonacciChip::construct(config),
+            layouter,
+            self.0,
+        )
+    }
+}
+
+struct AddXorCircuit<F>(usize, PhantomData<F>);
+
+impl<F: FieldExt> Circuit<F> for AddXorCircuit<F> {
+    type Config = add_xor::FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self(self.0, PhantomData)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        add_xor::FibonacciChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        synthesize_fibonacci(&add_xor::FibonacciChip::construct(config), layouter, self.0)
+    }
+}
+
+#[derive(Default)]
+struct FunctionCircuit<F> {
+    a: F,
+    b: F,
+    c: F,
+}
+
+impl<F: FieldExt> Circuit<F> for FunctionCircuit<F> {
+    type Config = function::FunctionConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        function::FunctionChip::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = function::FunctionChip::construct(config);
+        chip.assign(layouter, self.a, self.b, self.c)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn three_column_fibonacci() {
+    let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(55)];
+    let prover =
+        MockProver::run(4, &ThreeColumnCircuit(10, PhantomData), vec![public_input]).unwrap();
+    prover.assert_satisfied();
+}
+
+#[test]
+fn single_column_fibonacci() {
+    let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(55)];
+    let prover =
+        MockProver::run(4, &SingleColumnCircuit(10, PhantomData), vec![public_input]).unwrap();
+    prover.assert_satisfied();
+}
+
+#[test]
+fn add_xor_fibonacci() {
+    let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(21)];
+    let prover = MockProver::run(11, &AddXorCircuit(10, PhantomData), vec![public_input]).unwrap();
+    prover.assert_satisfied();
+}
+
+#[test]
+fn fibonacci_chips_reject_short_sequences() {
+    for nterms in 0..3 {
+        let public_input = vec![Fp::from(1), Fp::from(1), Fp::from(1)];
+        for result in [
+            MockProver::run(
+                4,
+                &ThreeColumnCircuit(nterms, PhantomData),
+                vec![public_input.clone()],
+            ),
+            MockProver::run(
+                4,
+                &SingleColumnCircuit(nterms, PhantomData),
+                vec![public_input.clone()],
+            ),
+            MockProver::run(
+                11,
+                &AddXorCircuit(nterms, PhantomData),
+                vec![public_input.clone()],
+            ),
+        ] {
+            assert!(matches!(result, Err(Error::Synthesis)));
+        }
+    }
+}
+
+#[test]
+fn function_chip() {
+    let circuit = FunctionCircuit {
+        a: Fp::from(10),
+        b: Fp::from(12),
+        c: Fp::from(15),
+    };
+    let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+    prover.assert_satisfied();
+}
+
+#[test]
+fn fibonacci_circuits_for_any_n() {
+    let n = 50;
+    let (mut a, mut b) = (Fp::from(1), Fp::from(1));
+    for _ in 0..n {
+        let c = a + b;
+        a = b;
+        b = c;
+    }
+    let public_input = vec![Fp::from(1), Fp::from(1), a];
+
+    let circuit = three_column::FibonacciCircuit::new(n);
+    let prover = MockProver::run(circuit.k(), &circuit, vec![public_input.clone()]).unwrap();
+    prover.assert_satisfied();
+
+    let circuit = single_column::FibonacciCircuit::new(n);
+    let prover = MockProver::run(circuit.k(), &circuit, vec![public_input]).unwrap();
+    prover.assert_satisfied();
+}
@@ -0,0 +1,488 @@
+use halo2_examples::gadgets::is_zero::{
+    IsZeroBatchChip, IsZeroBatchConfig, IsZeroChip, IsZeroConfig, IsZeroWithOutputChip,
+    IsZeroWithOutputConfig,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    pasta::Fp,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+#[derive(Debug, Clone)]
+struct TestConfig<F: FieldExt> {
+    selector: Selector,
+    value: Column<Advice>,
+    expected: Column<Advice>,
+    is_zero: IsZeroConfig<F>,
+}
+
+/// Witnesses `value` and checks that `is_zero(value) == expected`.
+#[derive(Default)]
+struct TestCircuit<F> {
+    value: F,
+    expected: F,
+}
+
+impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+    type Config = TestConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let selector = meta.selector();
+        let value = meta.advice_column();
+        let expected = meta.advice_column();
+        let value_inv = meta.advice_column();
+
+        let is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            value_inv,
+        );
+
+        meta.create_gate("is_zero(value) == expected", |meta| {
+            let s = meta.query_selector(selector);
+            let expected = meta.query_advice(expected, Rotation::cur());
+            vec![s * (is_zero.expr() - expected)]
+        });
+
+        TestConfig {
+            selector,
+            value,
+            expected,
+            is_zero,
+        }
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = IsZeroChip::construct(config.is_zero.clone());
+
+        layouter.assign_region(
+            || "is zero",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", config.value, 0, || Value::known(self.value))?;
+                region.assign_advice(
+                    || "expected",
+                    config.expected,
+                    0,
+                    || Value::known(self.expected),
+                )?;
+                chip.assign(&mut region, 0, Value::known(self.value))
+            },
+        )
+    }
+}
+
+#[test]
+fn is_zero_from_outside_the_crate() {
+    let k = 4;
+
+    for (value, expected) in [(0, 1), (5, 0)] {
+        let circuit = TestCircuit {
+            value: Fp::from(value),
+            expected: Fp::from(expected),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    let circuit = TestCircuit {
+        value: Fp::from(5),
+        expected: Fp::one(),
+    };
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[derive(Debug, Clone)]
+struct OutputConfig<F: FieldExt> {
+    selector: Selector,
+    value: Column<Advice>,
+    instance: Column<Instance>,
+    is_zero: IsZeroWithOutputConfig<F>,
+}
+
+/// Witnesses `value` and exposes the cell holding `is_zero(value)`.
+#[derive(Default)]
+struct OutputCircuit<F> {
+    value: F,
+}
+
+impl<F: FieldExt> Circuit<F> for OutputCircuit<F> {
+    type Config = OutputConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let selector = meta.selector();
+        let value = meta.advice_column();
+        let value_inv = meta.advice_column();
+        let output = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let is_zero = IsZeroWithOutputChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            value_inv,
+            output,
+        );
+
+        OutputConfig {
+            selector,
+            value,
+            instance,
+            is_zero,
+        }
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = IsZeroWithOutputChip::construct(config.is_zero.clone());
+
+        let out = layouter.assign_region(
+            || "is zero",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", config.value, 0, || Value::known(self.value))?;
+                chip.assign(&mut region, 0, Value::known(self.value))
+            },
+        )?;
+        layouter.constrain_instance(out.cell(), config.instance, 0)
+    }
+}
+
+#[test]
+fn is_zero_with_output_cell() {
+    let k = 4;
+
+    for (value, expected) in [(0, 1), (5, 0)] {
+        let circuit = OutputCircuit {
+            value: Fp::from(value),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(expected)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(1 - expected)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StackedConfig<F: FieldExt> {
+    selector: Selector,
+    value: Column<Advice>,
+    expected: Column<Advice>,
+    is_zero: [IsZeroConfig<F>; 2],
+}
+
+/// Checks two values, on rows 0 and 1, from a single gate row. Both inverses live in one
+/// column, each on the row of its value.
+#[derive(Default)]
+struct StackedCircuit<F> {
+    values: [F; 2],
+    expected: [F; 2],
+}
+
+impl<F: FieldExt> Circuit<F> for StackedCircuit<F> {
+    type Config = StackedConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let selector = meta.selector();
+        let value = meta.advice_column();
+        let expected = meta.advice_column();
+        let value_inv = meta.advice_column();
+
+        let is_zero = [Rotation::cur(), Rotation::next()].map(|rotation| {
+            IsZeroChip::configure_with_rotation(
+                meta,
+                |meta| meta.query_selector(selector),
+                |meta| meta.query_advice(value, rotation),
+                value_inv,
+                rotation,
+            )
+        });
+
+        meta.create_gate("is_zero(value) == expected", |meta| {
+            let s = meta.query_selector(selector);
+            is_zero
+                .iter()
+                .map(|is_zero| {
+                    let expected = meta.query_advice(expected, is_zero.rotation);
+                    s.clone() * (is_zero.expr() - expected)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        StackedConfig {
+            selector,
+            value,
+            expected,
+            is_zero,
+        }
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "stacked is zero",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                for row in 0..2 {
+                    let value = Value::known(self.values[row]);
+                    region.assign_advice(|| "value", config.value, row, || value)?;
+                    region.assign_advice(
+                        || "expected",
+                        config.expected,
+                        row,
+                        || Value::known(self.expected[row]),
+                    )?;
+                    IsZeroChip::construct(config.is_zero[row].clone()).assign(&mut region, 0, value)?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn is_zero_with_rotation() {
+    let k = 4;
+
+    for (values, expected) in [([0, 7], [1, 0]), ([7, 0], [0, 1]), ([0, 0], [1, 1])] {
+        let circuit = StackedCircuit {
+            values: values.map(Fp::from),
+            expected: expected.map(Fp::from),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    let circuit = StackedCircuit {
+        values: [Fp::from(7), Fp::zero()],
+        expected: [Fp::zero(), Fp::zero()],
+    };
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+/// Reads the inverse one row above the gate, which is outside the region at offset 0.
+#[derive(Default)]
+struct PrevRowCircuit<F> {
+    offset: usize,
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for PrevRowCircuit<F> {
+    type Config = (Selector, Column<Advice>, IsZeroConfig<F>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            offset: self.offset,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let selector = meta.selector();
+        let value = meta.advice_column();
+        let value_inv = meta.advice_column();
+
+        let is_zero = IsZeroChip::configure_with_rotation(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            value_inv,
+            Rotation::prev(),
+        );
+
+        (selector, value, is_zero)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let (selector, value, is_zero) = config;
+        layouter.assign_region(
+            || "prev row is zero",
+            |mut region| {
+                selector.enable(&mut region, self.offset)?;
+                let v = Value::known(F::from(5));
+                region.assign_advice(|| "value", value, self.offset, || v)?;
+                IsZeroChip::construct(is_zero.clone()).assign(&mut region, self.offset, v)
+            },
+        )
+    }
+}
+
+#[test]
+fn is_zero_rotation_above_region() {
+    let k = 4;
+
+    let circuit = PrevRowCircuit::<Fp> {
+        offset: 1,
+        ..Default::default()
+    };
+    MockProver::run(k, &circuit, vec![]).unwrap().assert_satisfied();
+
+    // At offset 0 the inverse would land on row -1.
+    let circuit = PrevRowCircuit::<Fp>::default();
+    assert!(matches!(
+        MockProver::run(k, &circuit, vec![]),
+        Err(Error::Synthesis)
+    ));
+}
+
+const BATCH: usize = 4;
+
+#[derive(Debug, Clone)]
+struct BatchConfig<F: FieldExt> {
+    selector: Selector,
+    value: Column<Advice>,
+    expected: Column<Advice>,
+    is_zero: IsZeroBatchConfig<F, BATCH>,
+}
+
+/// Checks `BATCH` values, stacked in one column, with a single batched gate.
+#[derive(Default)]
+struct BatchCircuit<F> {
+    values: [F; BATCH],
+    expected: [F; BATCH],
+}
+
+impl<F: FieldExt> Circuit<F> for BatchCircuit<F> {
+    type Config = BatchConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let selector = meta.selector();
+        let value = meta.advice_column();
+        let expected = meta.advice_column();
+        let value_inv = meta.advice_column();
+
+        let is_zero = IsZeroBatchChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| std::array::from_fn(|i| meta.query_advice(value, Rotation(i as i32))),
+            value_inv,
+        );
+
+        meta.create_gate("is_zero(value) == expected", |meta| {
+            let s = meta.query_selector(selector);
+            (0..BATCH)
+                .map(|i| {
+                    let expected = meta.query_advice(expected, Rotation(i as i32));
+                    s.clone() * (is_zero.expr(i) - expected)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        BatchConfig {
+            selector,
+            value,
+            expected,
+            is_zero,
+        }
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = IsZeroBatchChip::construct(config.is_zero.clone());
+
+        layouter.assign_region(
+            || "batched is zero",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                for row in 0..BATCH {
+                    region.assign_advice(
+                        || "value",
+                        config.value,
+                        row,
+                        || Value::known(self.values[row]),
+                    )?;
+                    region.assign_advice(
+                        || "expected",
+                        config.expected,
+                        row,
+                        || Value::known(self.expected[row]),
+                    )?;
+                }
+                chip.assign_many(&mut region, 0, self.values.map(Value::known))
+            },
+        )
+    }
+}
+
+#[test]
+fn is_zero_batch() {
+    let k = 5;
+
+    let circuit = BatchCircuit {
+        values: [0, 3, 0, 9].map(Fp::from),
+        expected: [1, 0, 1, 0].map(Fp::from),
+    };
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    prover.assert_satisfied();
+
+    let circuit = BatchCircuit {
+        values: [0, 3, 0, 9].map(Fp::from),
+        expected: [1, 0, 0, 0].map(Fp::from),
+    };
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn is_zero_batch_budget() {
+    // Values in their own columns, as in a single row of a wider circuit.
+    let mut meta = ConstraintSystem::<Fp>::default();
+    let selector = meta.selector();
+    let values: [Column<Advice>; BATCH] = std::array::from_fn(|_| meta.advice_column());
+    let value_inv = meta.advice_column();
+    IsZeroBatchChip::configure(
+        &mut meta,
+        |meta| meta.query_selector(selector),
+        |meta| values.map(|value| meta.query_advice(value, Rotation::cur())),
+        value_inv,
+    );
+
+    // One inverse column, one selector and one gate for the whole batch, at the same degree
+    // as a single check.
+    assert_eq!(meta.num_advice_columns(), BATCH + 1);
+    assert_eq!(meta.num_selectors(), 1);
+    assert_eq!(meta.gates().len(), 1);
+    assert!(meta.degree() <= 4);
+
+    // Configuring the checks one by one needs an inverse column and a gate each.
+    let mut unbatched = ConstraintSystem::<Fp>::default();
+    let selector = unbatched.selector();
+    let values: [Column<Advice>; BATCH] = std::array::from_fn(|_| unbatched.advice_column());
+    for value in values {
+        let value_inv = unbatched.advice_column();
+        IsZeroChip::configure(
+            &mut unbatched,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            value_inv,
+        );
+    }
+    assert_eq!(unbatched.num_advice_columns(), 2 * BATCH);
+    assert_eq!(unbatched.gates().len(), BATCH);
+}
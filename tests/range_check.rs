@@ -0,0 +1,109 @@
+use halo2_examples::gadgets::range_check::{expression, lookup};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{floor_planner::V1, Layouter, Value},
+    dev::MockProver,
+    pasta::Fp,
+    plonk::{Assigned, Circuit, ConstraintSystem, Error},
+};
+
+#[derive(Default)]
+struct ExpressionCircuit<F: FieldExt, const RANGE: usize> {
+    value: Value<Assigned<F>>,
+}
+
+impl<F: FieldExt, const RANGE: usize> Circuit<F> for ExpressionCircuit<F, RANGE> {
+    type Config = expression::RangeCheckConfig<F, RANGE>;
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        expression::RangeCheckConfig::configure(meta, value)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let constrained = config.assign(layouter.namespace(|| "Assign value"), self.value)?;
+        constrained
+            .value()
+            .zip(self.value)
+            .assert_if_known(|(got, want)| got.evaluate() == want.evaluate());
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct LookupCircuit<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> {
+    value: Value<Assigned<F>>,
+    lookup_value: Value<Assigned<F>>,
+}
+
+impl<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> Circuit<F>
+    for LookupCircuit<F, RANGE, LOOKUP_RANGE>
+{
+    type Config = lookup::RangeCheckConfig<F, RANGE, LOOKUP_RANGE>;
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        lookup::RangeCheckConfig::configure(meta, value)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+
+        config.assign_simple(layouter.namespace(|| "Assign simple value"), self.value)?;
+        config.assign_lookup(
+            layouter.namespace(|| "Assign lookup value"),
+            self.lookup_value,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn expression_range_check() {
+    const RANGE: usize = 8;
+
+    for i in 0..RANGE {
+        let circuit = ExpressionCircuit::<Fp, RANGE> {
+            value: Value::known(Fp::from(i as u64).into()),
+        };
+        let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    let circuit = ExpressionCircuit::<Fp, RANGE> {
+        value: Value::known(Fp::from(RANGE as u64).into()),
+    };
+    let prover = MockProver::run(4, &circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn lookup_range_check() {
+    const RANGE: usize = 8;
+    const LOOKUP_RANGE: usize = 256;
+
+    let circuit = LookupCircuit::<Fp, RANGE, LOOKUP_RANGE> {
+        value: Value::known(Fp::from(5).into()),
+        lookup_value: Value::known(Fp::from(5).into()),
+    };
+    let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+    prover.assert_satisfied();
+
+    let circuit = LookupCircuit::<Fp, RANGE, LOOKUP_RANGE> {
+        value: Value::known(Fp::from(5).into()),
+        lookup_value: Value::known(Fp::from(LOOKUP_RANGE as u64).into()),
+    };
+    let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}